@@ -0,0 +1,90 @@
+//! Measures how often Talc's reallocations grow in place instead of relocating,
+//! for buffers grown by a few different factors, with and without other allocations
+//! being made in between.
+//!
+//! Run with `cargo run -p benchmarks --bin realloc_in_place --features counters --release`
+
+use std::{
+    alloc::{GlobalAlloc, Layout},
+    time::Instant,
+};
+
+use talc::{ErrOnOom, Talc};
+
+const HEAP_SIZE: usize = 1 << 26;
+const BUFFER_COUNT: usize = 64;
+const STEPS: usize = 20000;
+const INITIAL_SIZE: usize = 16;
+const MAX_SIZE: usize = 1 << 16;
+
+fn main() {
+    println!("| Growth Factor | Interleaved Allocations | Grown In-Place | Relocated | Time per Realloc |");
+    println!("| ------------- | ----------------------- | -------------- | --------- | ---------------- |");
+
+    for growth_factor in [1.25, 1.5, 2.0] {
+        for interleaved in [false, true] {
+            benchmark(growth_factor, interleaved);
+        }
+    }
+}
+
+fn benchmark(growth_factor: f64, interleaved: bool) {
+    let mut heap = vec![0u8; HEAP_SIZE];
+    let talck = Talc::new(ErrOnOom).lock::<spin::Mutex<()>>();
+    unsafe { talck.lock().claim(heap.as_mut_slice().into()) }.unwrap();
+
+    let rng = fastrand::Rng::with_seed(0);
+    let mut buffers = [(); BUFFER_COUNT].map(|_| {
+        let layout = Layout::from_size_align(INITIAL_SIZE, 8).unwrap();
+        (unsafe { talck.alloc(layout) }, layout)
+    });
+    let mut others = Vec::new();
+
+    let baseline = *talck.lock().get_counters();
+    let start = Instant::now();
+
+    for _ in 0..STEPS {
+        let (ptr, layout) = &mut buffers[rng.usize(..BUFFER_COUNT)];
+
+        if interleaved {
+            let other = Layout::from_size_align(rng.usize(8..512), 8).unwrap();
+            others.push((unsafe { talck.alloc(other) }, other));
+
+            if others.len() > BUFFER_COUNT * 4 {
+                let (ptr, layout) = others.swap_remove(rng.usize(..others.len()));
+                unsafe { talck.dealloc(ptr, layout) };
+            }
+        }
+
+        // start over once the buffer gets big, much like clearing and shrinking a Vec
+        let new_size = if layout.size() >= MAX_SIZE {
+            INITIAL_SIZE
+        } else {
+            (layout.size() as f64 * growth_factor) as usize
+        };
+
+        *ptr = unsafe { talck.realloc(*ptr, *layout, new_size) };
+        assert!(!ptr.is_null());
+        *layout = Layout::from_size_align(new_size, 8).unwrap();
+    }
+
+    let elapsed = start.elapsed();
+    let delta = talck.lock().get_counters().diff(&baseline);
+
+    let in_place = delta.total_grow_in_place_count;
+    let relocated = delta.total_relocation_count;
+    let total = (in_place + relocated).max(1) as f64;
+
+    println!(
+        "| {:>13} | {:>23} | {:>13.1}% | {:>8.1}% | {:>16.0?} |",
+        growth_factor,
+        interleaved,
+        in_place as f64 / total * 100.0,
+        relocated as f64 / total * 100.0,
+        elapsed / STEPS as u32,
+    );
+
+    for (ptr, layout) in buffers.into_iter().chain(others) {
+        unsafe { talck.dealloc(ptr, layout) };
+    }
+}
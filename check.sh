@@ -16,6 +16,7 @@ rustup run stable cargo check -p talc --no-default-features --features=lock_api,
 # check that the examples work
 rustup run stable cargo check -p stable_examples --example stable_allocator_api
 rustup run stable cargo check -p stable_examples --example std_global_allocator
+rustup run stable cargo check -p stable_examples --example static_global_cell
 
 # check whether MSRV has been broken
 rustup run 1.67.1 cargo check -p talc --no-default-features --features lock_api,allocator-api2,counters
@@ -26,15 +27,20 @@ rustup run 1.67.1 cargo check -p talc --no-default-features --features lock_api,
 rustup run nightly cargo check -p talc
 
 rustup run nightly cargo test -p talc --features=counters
+rustup run nightly cargo test -p talc --features=alloc,best-fit,realloc-shift-down,metrics,histogram,leak-attribution,log-oom,allocate-at-least,lock-metrics,scan-metrics
+rustup run nightly cargo test -p talc --features=next-fit,counters
+rustup run nightly cargo test -p talc --features=hot-cache,counters hot_cache
 rustup run nightly cargo test -p talc --tests --no-default-features
 rustup run nightly cargo test -p talc --tests --no-default-features --features=lock_api,allocator-api2,counters
+rustup run nightly cargo test -p talc --release --features=counters release_
 
-rustup run nightly cargo miri test -p talc --tests
-rustup run nightly cargo miri test -p talc --tests --target i686-unknown-linux-gnu
+MIRIFLAGS="-Zmiri-strict-provenance" rustup run nightly cargo miri test -p talc --tests
+MIRIFLAGS="-Zmiri-strict-provenance" rustup run nightly cargo miri test -p talc --tests --target i686-unknown-linux-gnu
 
 # check the benchmarks
 rustup run nightly cargo check -p benchmarks --bin microbench
 rustup run nightly cargo check -p benchmarks --bin random_actions
+rustup run nightly cargo check -p benchmarks --bin realloc_in_place --features=counters
 
 
 # WASM BENCHMARKS CHECK
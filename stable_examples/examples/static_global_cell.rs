@@ -0,0 +1,42 @@
+use core::mem::MaybeUninit;
+
+use talc::{locking::AssumeUnlockable, *};
+
+// Run with:
+// `cargo +stable run -p stable_examples --example static_global_cell`
+// `cargo miri run -p stable_examples --example static_global_cell`
+
+// Notes:
+//
+// ## Using `AssumeUnlockable`
+// On single-threaded targets (e.g. bare-metal microcontrollers without atomics)
+// synchronization can be skipped entirely with `AssumeUnlockable`.
+// This is only sound if the allocator is never used from more than one thread at once.
+// If in doubt, use `spin::Mutex<()>` instead, see `std_global_allocator.rs`.
+//
+// ## Using `ClaimOnOom`
+// The arena is claimed upon the first allocation, so no setup is required in `main`.
+// This is also necessary in hosted environments, as allocations may occur prior to `main`.
+//
+// ## The arena
+// The arena doesn't need to be initialized, hence `MaybeUninit`. It doesn't need any
+// particular alignment either, Talc aligns the heap's boundaries inward as necessary.
+
+const ARENA_SIZE: usize = 10000;
+
+static mut ARENA: [MaybeUninit<u8>; ARENA_SIZE] = [MaybeUninit::uninit(); ARENA_SIZE];
+
+// SAFETY: this program is single-threaded, thus the allocator is never accessed concurrently.
+#[global_allocator]
+static ALLOCATOR: Talck<AssumeUnlockable, ClaimOnOom> =
+    Talc::new(unsafe { ClaimOnOom::new(Span::from_array(&raw mut ARENA)) }).lock();
+
+fn main() {
+    let mut vec = Vec::with_capacity(100);
+    vec.extend(0..300usize);
+    vec.truncate(100);
+    vec.shrink_to_fit();
+
+    assert!(vec.iter().copied().eq(0..100usize));
+    drop(vec);
+}
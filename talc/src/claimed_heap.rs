@@ -0,0 +1,221 @@
+//! Home of ClaimedHeap, a handle that keeps track of a heap's current extent.
+
+use crate::{OomHandler, Span, Talc};
+
+/// A heap claimed by a [`Talc`], which keeps track of the heap's current extent.
+///
+/// [`Talc::extend`] and [`Talc::truncate`] require the heap's current extent, as returned
+/// by the last heap-manipulation function called on it. Passing a stale extent corrupts
+/// the allocator. `ClaimedHeap` updates its extent in place whenever it's resized through
+/// it, and isn't [`Copy`] or [`Clone`], so it can't go stale.
+///
+/// It doesn't borrow the [`Talc`], so it can be kept alongside it, e.g. in an [`OomHandler`].
+/// The raw [`Span`]-based functions remain available for advanced use.
+///
+/// As a consequence, it's up to the caller to pass the allocator that claimed the heap.
+/// In debug builds, heaps created by [`claim`](ClaimedHeap::claim) record their allocator
+/// and panic if used with another. This isn't checked in release builds, nor for heaps
+/// adopted with [`from_span`](ClaimedHeap::from_span).
+///
+/// # Example
+/// ```rust
+/// # use talc::*;
+/// let mut arena = [0u8; 10000];
+/// let arena_span = Span::from(&mut arena);
+///
+/// let mut talc = Talc::new(ErrOnOom);
+/// let mut heap = unsafe { ClaimedHeap::claim(&mut talc, arena_span.truncate(0, 5000)) }.unwrap();
+///
+/// unsafe { heap.extend(&mut talc, arena_span) };
+/// assert_eq!(heap.span(), arena_span.word_align_inward());
+///
+/// let allocated = unsafe { heap.allocated_span(&talc) };
+/// unsafe { heap.truncate(&mut talc, allocated) };
+/// assert_eq!(heap.span(), allocated);
+/// ```
+#[derive(Debug)]
+pub struct ClaimedHeap {
+    span: Span,
+    /// The [`Talc::metadata_addr`] of the allocator that claimed the heap, if known.
+    #[cfg(debug_assertions)]
+    owner: Option<usize>,
+}
+
+impl PartialEq for ClaimedHeap {
+    fn eq(&self, other: &Self) -> bool {
+        self.span == other.span
+    }
+}
+
+impl Eq for ClaimedHeap {}
+
+impl ClaimedHeap {
+    /// Claim `memory` as a new heap of `talc`. See [`Talc::claim`].
+    ///
+    /// # Safety
+    /// The requirements of [`Talc::claim`] for `memory`.
+    ///
+    /// # Panics
+    /// Panics if `memory` contains the null address.
+    #[track_caller]
+    #[must_use = "the returned handle is required to extend or truncate the heap"]
+    pub unsafe fn claim<O: OomHandler>(talc: &mut Talc<O>, memory: Span) -> Result<Self, ()> {
+        talc.claim(memory).map(|span| Self {
+            span,
+            #[cfg(debug_assertions)]
+            owner: Some(talc.metadata_addr()),
+        })
+    }
+
+    /// Adopt a heap that was established through the raw API.
+    ///
+    /// # Safety
+    /// `span` must be the return value of the last heap-manipulation function
+    /// called on the heap, and mustn't be used to manipulate the heap hereafter.
+    pub unsafe fn from_span(span: Span) -> Self {
+        Self {
+            span,
+            #[cfg(debug_assertions)]
+            owner: None,
+        }
+    }
+
+    /// Returns the heap's current extent.
+    ///
+    /// This is empty if the heap was truncated away, in which case it no longer exists.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Returns the span within the heap containing all its allocations.
+    /// See [`Talc::get_allocated_span`].
+    ///
+    /// # Safety
+    /// `talc` must be the allocator that the heap was claimed by.
+    ///
+    /// # Panics
+    /// Panics if the heap was truncated away, or in debug builds, if `talc` didn't claim it.
+    #[track_caller]
+    pub unsafe fn allocated_span<O: OomHandler>(&self, talc: &Talc<O>) -> Span {
+        self.debug_check_owner(talc);
+
+        talc.get_allocated_span(self.span)
+    }
+
+    /// Extend the heap to `req_heap`, or slightly less, returning the new extent.
+    /// See [`Talc::extend`].
+    ///
+    /// # Safety
+    /// - `talc` must be the allocator that the heap was claimed by.
+    /// - The requirements of [`Talc::extend`] for `req_heap`.
+    ///
+    /// # Panics
+    /// Panics if the heap was truncated away, or in debug builds, if `talc` didn't claim it.
+    /// Also panics as described by [`Talc::extend`].
+    #[track_caller]
+    pub unsafe fn extend<O: OomHandler>(&mut self, talc: &mut Talc<O>, req_heap: Span) -> Span {
+        self.debug_check_owner(talc);
+
+        self.span = talc.extend(self.span, req_heap);
+        self.span
+    }
+
+    /// Truncate the heap to `req_heap`, or slightly less, returning the new extent.
+    /// See [`Talc::truncate`].
+    ///
+    /// If the result is empty, the heap no longer exists, and `self` can't be resized again.
+    ///
+    /// # Safety
+    /// `talc` must be the allocator that the heap was claimed by.
+    ///
+    /// # Panics
+    /// Panics if the heap was truncated away, or in debug builds, if `talc` didn't claim it.
+    /// Also panics as described by [`Talc::truncate`].
+    #[track_caller]
+    pub unsafe fn truncate<O: OomHandler>(&mut self, talc: &mut Talc<O>, req_heap: Span) -> Span {
+        assert!(!self.span.is_empty(), "the heap was truncated away");
+        self.debug_check_owner(talc);
+
+        self.span = talc.truncate(self.span, req_heap);
+        self.span
+    }
+
+    /// In debug builds, panics if `talc` isn't the allocator that claimed the heap, where known.
+    #[track_caller]
+    fn debug_check_owner<O: OomHandler>(&self, talc: &Talc<O>) {
+        #[cfg(debug_assertions)]
+        if let Some(owner) = self.owner {
+            assert!(owner == talc.metadata_addr(), "the heap was claimed by a different allocator");
+        }
+
+        #[cfg(not(debug_assertions))]
+        let _ = talc;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrOnOom;
+    use core::alloc::Layout;
+
+    #[test]
+    fn claimed_heap_test() {
+        let mut metadata = [0u8; 4000];
+        let mut arena = [0u8; 10000];
+        let arena_span = Span::from(&mut arena);
+
+        // keep the metadata out of the heap, so that it can be truncated away
+        let mut talc = Talc::new(ErrOnOom);
+        unsafe { talc.establish_metadata(Span::from(&mut metadata)).unwrap() };
+        let mut heap =
+            unsafe { ClaimedHeap::claim(&mut talc, arena_span.truncate(4000, 4000)) }.unwrap();
+
+        let layout = Layout::new::<[u8; 1000]>();
+        let ptr = unsafe { talc.malloc(layout).unwrap() };
+
+        // repeated resizing always uses the current extent
+        for _ in 0..3 {
+            let extended = unsafe { heap.extend(&mut talc, arena_span) };
+            assert_eq!(extended, heap.span());
+            assert!(heap.span().contains(ptr.as_ptr()));
+
+            let allocated = unsafe { heap.allocated_span(&talc) };
+            let truncated = unsafe { heap.truncate(&mut talc, allocated) };
+            assert_eq!(truncated, heap.span());
+            assert!(truncated.size() < extended.size());
+        }
+
+        // once empty, the heap is gone
+        unsafe { talc.free(ptr, layout) };
+        unsafe { heap.truncate(&mut talc, Span::empty()) };
+        assert!(heap.span().is_empty());
+
+        let mut talc = std::panic::AssertUnwindSafe(&mut talc);
+        let mut heap = std::panic::AssertUnwindSafe(heap);
+        let truncate_again =
+            std::panic::catch_unwind(move || unsafe { heap.truncate(&mut talc, Span::empty()) });
+        assert!(truncate_again.is_err());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn claimed_heap_wrong_allocator_test() {
+        let mut arena_a = [0u8; 10000];
+        let mut arena_b = [0u8; 10000];
+
+        let mut talc_a = Talc::new(ErrOnOom);
+        let mut talc_b = Talc::new(ErrOnOom);
+        let heap = unsafe { ClaimedHeap::claim(&mut talc_a, Span::from(&mut arena_a)) }.unwrap();
+        unsafe { talc_b.claim(Span::from(&mut arena_b)).unwrap() };
+
+        let wrong_allocator = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            heap.allocated_span(&talc_b)
+        }));
+        assert!(wrong_allocator.is_err());
+
+        // adopted heaps don't know their allocator, but still compare by extent
+        let adopted = unsafe { ClaimedHeap::from_span(heap.span()) };
+        assert_eq!(adopted, heap);
+    }
+}
@@ -8,23 +8,51 @@
 //! Calling `Talc::lock()` on it will yield a `Talck` which implements
 //! [`GlobalAlloc`] and [`Allocator`] (if the appropriate feature flags are set).
 
-#![cfg_attr(not(any(test, feature = "fuzzing")), no_std)]
+#![cfg_attr(not(any(test, feature = "fuzzing", feature = "std")), no_std)]
 #![cfg_attr(feature = "allocator", feature(allocator_api))]
 
+// Chunk tags keep two flags in the low bits of a pointer, and binning is tuned per word size.
+// Neither holds up on 16-bit targets, where pointers are only guaranteed to be 2-byte aligned.
+#[cfg(not(any(target_pointer_width = "32", target_pointer_width = "64")))]
+compile_error!(
+    "Talc only supports 32-bit and 64-bit targets. \
+    For 16-bit targets, consider a bump or fixed-block allocator instead."
+);
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod claimed_heap;
 mod oom_handler;
-mod ptr_utils;
 mod span;
 mod talc;
 
+pub mod ptr_utils;
+
 #[cfg(feature = "lock_api")]
 pub mod locking;
 #[cfg(feature = "lock_api")]
 mod talck;
 
+#[cfg(any(feature = "allocator", feature = "allocator-api2"))]
+pub mod testing;
+
+#[cfg(feature = "alloc")]
+pub use oom_handler::OwnedHeap;
+pub use claimed_heap::ClaimedHeap;
 pub use oom_handler::{ClaimOnOom, ErrOnOom, OomHandler};
 pub use span::Span;
 pub use talc::Talc;
 
+#[cfg(feature = "counters")]
+pub use talc::counters::{Counters, CountersDelta};
+
+#[cfg(feature = "atomic-counters")]
+pub use talck::LockFreeCounters;
+#[cfg(feature = "lock-metrics")]
+pub use talck::LockMetrics;
+#[cfg(feature = "metrics")]
+pub use talck::MetricNames;
 #[cfg(feature = "lock_api")]
 pub use talck::Talck;
 #[cfg(all(target_family = "wasm", feature = "lock_api"))]
@@ -1,7 +1,52 @@
 use core::alloc::Layout;
+#[cfg(feature = "alloc")]
+use core::mem::MaybeUninit;
 
 use crate::{Span, Talc};
 
+/// Handles out-of-memory conditions, typically by providing [`Talc`] with more memory.
+///
+/// [`Talc`] doesn't release any memory when dropped. A handler that acquires memory
+/// (e.g. from an OS or a parent allocator) should keep track of it and release it
+/// in its own [`Drop`] implementation, which runs when the [`Talc`] is dropped.
+/// See `OwnedHeap` (with the `"alloc"` feature) for an example.
+///
+/// # Combining handlers
+/// Handlers can't delegate to one another, as [`handle_oom`](OomHandler::handle_oom) is
+/// given a `Talc<Self>` rather than a `Talc` of the inner handler's type. Instead, keep the
+/// state of each strategy in one handler and try them in turn. For example, claiming an
+/// arena first, then falling back to the global allocator:
+/// ```rust
+/// # use talc::*;
+/// use core::alloc::Layout;
+///
+/// struct ArenaThenGlobal {
+///     arena: Span,
+/// }
+///
+/// impl OomHandler for ArenaThenGlobal {
+///     fn handle_oom(talc: &mut Talc<Self>, layout: Layout) -> Result<(), ()> {
+///         // prefer the arena, which is only claimed once
+///         let arena = core::mem::take(&mut talc.oom_handler.arena);
+///         if !arena.is_empty() && unsafe { talc.claim(arena) }.is_ok() {
+///             return Ok(());
+///         }
+///
+///         // then fall back to the global allocator, leaking the memory for brevity
+///         let size = (layout.size() + layout.align()).max(1 << 16);
+///         let memory = Box::leak(vec![0u8; size].into_boxed_slice());
+///         unsafe { talc.claim(memory.into()) }.map(|_| ())
+///     }
+/// }
+///
+/// let mut arena = [0u8; 4000];
+/// let mut talc = Talc::new(ArenaThenGlobal { arena: Span::from(&mut arena) });
+///
+/// let small = unsafe { talc.malloc(Layout::new::<[u8; 100]>()) }.unwrap();
+/// assert!(Span::from(&mut arena).contains(small.as_ptr()));
+/// let big = unsafe { talc.malloc(Layout::new::<[u8; 5000]>()) }.unwrap();
+/// assert!(!Span::from(&mut arena).contains(big.as_ptr()));
+/// ```
 pub trait OomHandler: Sized {
     /// Given the allocator and the `layout` of the allocation that caused
     /// OOM, resize or claim and return `Ok(())` or fail by returning `Err(())`.
@@ -9,7 +54,22 @@ pub trait OomHandler: Sized {
     /// This function is called repeatedly if the allocator is still out of memory.
     /// Therefore an infinite loop will occur if `Ok(())` is repeatedly returned
     /// without extending or claiming new memory.
+    ///
+    /// If this panics, the allocation fails by unwinding. The allocator is left
+    /// in a consistent state as long as any heap manipulation by the handler
+    /// was completed, and a [`Talck`](crate::Talck)'s lock is released as usual.
+    ///
+    /// # Panics
+    /// In debug builds only, the allocator panics with "the OOM handler returned Ok but
+    /// provided no memory" if `Ok(())` is returned without any memory being made available.
+    /// To check this, the free memory is totalled before and after each call, which walks
+    /// every gap unless the `"counters"` feature is enabled.
     fn handle_oom(talc: &mut Talc<Self>, layout: Layout) -> Result<(), ()>;
+
+    /// Whether [`handle_oom`](OomHandler::handle_oom) always returns `Err(())`, like
+    /// [`ErrOnOom`]'s. If so, allocation fails immediately on OOM without calling it,
+    /// which is known at compile time. Defaults to `false`.
+    const NEVER_HANDLES_OOM: bool = false;
 }
 
 /// Doesn't handle out-of-memory conditions, immediate allocation error occurs.
@@ -19,6 +79,8 @@ impl OomHandler for ErrOnOom {
     fn handle_oom(_: &mut Talc<Self>, _: Layout) -> Result<(), ()> {
         Err(())
     }
+
+    const NEVER_HANDLES_OOM: bool = true;
 }
 
 /// An out-of-memory handler that attempts to claim the
@@ -54,6 +116,83 @@ impl OomHandler for ClaimOnOom {
     }
 }
 
+/// An out-of-memory handler that owns an arena allocated with the global allocator,
+/// claiming it upon OOM. The arena is freed when the handler is dropped, along with the [`Talc`].
+///
+/// This is convenient for tests, as it takes care of setting up and cleaning up the memory.
+///
+/// # Example
+/// ```rust
+/// # use talc::*;
+/// let talck = Talc::new(unsafe { OwnedHeap::new(5000) }).lock::<spin::Mutex<()>>();
+///
+/// let layout = core::alloc::Layout::new::<[u64; 16]>();
+/// let ptr = unsafe { talck.lock().malloc(layout).unwrap() };
+/// unsafe { talck.lock().free(ptr, layout) };
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct OwnedHeap {
+    /// Not a `Box`, as moving a `Box` asserts unique access to the arena.
+    base: *mut u8,
+    size: usize,
+    claimed: bool,
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl Send for OwnedHeap {}
+
+#[cfg(feature = "alloc")]
+impl OwnedHeap {
+    /// Allocate an arena of `size` bytes, to be claimed upon OOM.
+    ///
+    /// # Safety
+    /// The arena is freed when the `OwnedHeap` is dropped. Therefore it must not be
+    /// removed from the [`Talc`] it's given to (e.g. by replacing
+    /// [`oom_handler`](Talc::oom_handler)) while the `Talc` is still in use.
+    pub unsafe fn new(size: usize) -> Self {
+        let arena = alloc::vec![MaybeUninit::<u8>::uninit(); size].into_boxed_slice();
+        let base = alloc::boxed::Box::into_raw(arena).cast::<u8>();
+
+        Self { base, size, claimed: false }
+    }
+
+    /// Returns the [`Span`] of the arena.
+    pub fn span(&self) -> Span {
+        Span::from_base_size(self.base, self.size)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl OomHandler for OwnedHeap {
+    fn handle_oom(talc: &mut Talc<Self>, _: Layout) -> Result<(), ()> {
+        if !talc.oom_handler.claimed {
+            talc.oom_handler.claimed = true;
+
+            unsafe {
+                talc.claim(talc.oom_handler.span())?;
+            }
+
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Drop for OwnedHeap {
+    fn drop(&mut self) {
+        let arena =
+            core::ptr::slice_from_raw_parts_mut(self.base.cast::<MaybeUninit<u8>>(), self.size);
+        drop(unsafe { alloc::boxed::Box::from_raw(arena) });
+    }
+}
+
+/// An out-of-memory handler for WebAssembly that grows linear memory using `memory.grow`.
+///
+/// If the host refuses to grow memory by even a single page,
+/// allocation fails, instead of retrying indefinitely.
 #[cfg(all(target_family = "wasm", feature = "lock_api"))]
 pub struct WasmHandler {
     prev_heap: Span,
@@ -110,7 +249,10 @@ impl OomHandler for WasmHandler {
         };
 
         let prev_heap_acme = (prev * PAGE_SIZE) as *mut u8;
-        let new_heap_acme = prev_heap_acme.wrapping_add(delta_pages * PAGE_SIZE);
+        // if linear memory now spans the whole address space, the acme would wrap around to null
+        let delta_size =
+            delta_pages.saturating_mul(PAGE_SIZE).min(usize::MAX - prev_heap_acme as usize);
+        let new_heap_acme = prev_heap_acme.wrapping_add(delta_size);
 
         // try to get base & acme, which will fail if prev_heap is empty
         // otherwise the allocator has been initialized previously
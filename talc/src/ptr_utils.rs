@@ -1,12 +1,23 @@
 //! Generic utilities for pointer handling and sizing.
+//!
+//! These are the same helpers Talc uses internally, which is handy for computing
+//! memory regions to [`claim`](crate::Talc::claim) or writing an [`OomHandler`](crate::OomHandler)
+//! that agree with Talc on how pointers get aligned.
+//!
+//! None of these functions dereference the pointers they're given.
 
+use core::ptr::{null_mut, NonNull};
+
+/// The size of a machine word, `usize`, in bytes.
 pub const WORD_SIZE: usize = core::mem::size_of::<usize>();
+/// The size of a machine word, `usize`, in bits.
 pub const WORD_BITS: usize = usize::BITS as usize;
+/// The alignment of a machine word, `usize`. Heap boundaries are aligned to this.
 pub const ALIGN: usize = core::mem::align_of::<usize>();
 
-/// Aligns `ptr` up to the next `align_mask + 1`.
+/// Aligns `ptr` up to the next multiple of `align_mask + 1`.
 ///
-/// `align_mask` must be a power of two minus one.
+/// `align_mask` must be a power of two minus one. Wraps around on overflow.
 #[inline]
 pub fn align_up_by(ptr: *mut u8, align_mask: usize) -> *mut u8 {
     debug_assert!((align_mask + 1).is_power_of_two());
@@ -19,12 +30,47 @@ pub fn align_up_by(ptr: *mut u8, align_mask: usize) -> *mut u8 {
     // i.e. just align up to the next align_mask + 1
 }
 
+/// Aligns `ptr` down to the previous multiple of `align_mask + 1`.
+///
+/// `align_mask` must be a power of two minus one.
+#[inline]
+pub fn align_down_by(ptr: *mut u8, align_mask: usize) -> *mut u8 {
+    debug_assert!((align_mask + 1).is_power_of_two());
+
+    ptr.wrapping_sub(ptr as usize & align_mask)
+}
+
+/// Returns whether `ptr` is a multiple of `align`, which must be a power of two.
+#[inline]
+pub fn is_aligned_to(ptr: *mut u8, align: usize) -> bool {
+    (ptr as usize).trailing_zeros() >= align.trailing_zeros()
+}
+
+/// Returns a dangling pointer aligned to `align`, which must be a power of two.
+///
+/// This is the pointer Talc hands out for zero-sized allocations.
+#[inline]
+pub fn dangling(align: usize) -> NonNull<u8> {
+    debug_assert!(align.is_power_of_two());
+
+    // SAFETY: powers of two are nonzero
+    unsafe { NonNull::new_unchecked(null_mut::<u8>().wrapping_add(align)) }
+}
+
+/// Aligns `ptr` down to the previous multiple of [`ALIGN`].
+#[inline]
 pub fn align_down(ptr: *mut u8) -> *mut u8 {
     ptr.wrapping_sub(ptr as usize % ALIGN)
 }
+/// Returns whether aligning `ptr` up to the next multiple of [`ALIGN`] overflows.
+#[inline]
 pub fn align_up_overflows(ptr: *mut u8) -> bool {
     ALIGN - 1 > usize::MAX - ptr as usize
 }
+/// Aligns `ptr` up to the next multiple of [`ALIGN`].
+///
+/// Overflowing is a logic error, check [`align_up_overflows`] if it's possible.
+#[inline]
 pub fn align_up(ptr: *mut u8) -> *mut u8 {
     debug_assert!(!align_up_overflows(ptr));
 
@@ -64,4 +110,20 @@ mod tests {
                 == null_mut::<u8>().wrapping_add(ALIGN)
         );
     }
+    #[test]
+    fn align_by_test() {
+        let ptr = null_mut::<u8>().wrapping_add(0x1234);
+
+        assert!(align_up_by(ptr, 0x10 - 1) == null_mut::<u8>().wrapping_add(0x1240));
+        assert!(align_down_by(ptr, 0x10 - 1) == null_mut::<u8>().wrapping_add(0x1230));
+        assert!(align_up_by(ptr, 4 - 1) == ptr);
+        assert!(align_down_by(ptr, 4 - 1) == ptr);
+        assert!(align_up_by(ptr, 1 - 1) == ptr);
+
+        assert!(is_aligned_to(ptr, 4));
+        assert!(!is_aligned_to(ptr, 8));
+        assert!(is_aligned_to(null_mut(), 1 << (usize::BITS - 1)));
+
+        assert!(is_aligned_to(dangling(4096).as_ptr(), 4096));
+    }
 }
@@ -1,4 +1,4 @@
-use core::ops::Range;
+use core::{mem::MaybeUninit, ops::Range};
 
 use crate::ptr_utils::*;
 
@@ -11,7 +11,13 @@ use crate::ptr_utils::*;
 /// the specific values of `base` and `acme` are considered meaningless.
 /// * Empty spans contain nothing and overlap with nothing.
 /// * Empty spans are contained by any sized span.
+///
+/// # ABI
+/// [`Span`] is `#[repr(C)]`, laid out as two pointers, `base` then `acme`.
+/// It's equivalent to the C struct `struct { uint8_t *base; uint8_t *acme; }`
+/// and may be passed across FFI boundaries by value.
 #[derive(Clone, Copy, Hash)]
+#[repr(C)]
 pub struct Span {
     base: *mut u8,
     acme: *mut u8,
@@ -40,6 +46,8 @@ impl core::fmt::Display for Span {
     }
 }
 
+/// The span covers `start..end`. Inverted ranges, where `start > end`, yield an empty span,
+/// which converts back to `None` with [`Span::to_ptr_range`].
 impl<T> From<Range<*mut T>> for Span {
     fn from(value: Range<*mut T>) -> Self {
         Self { base: value.start.cast(), acme: value.end.cast() }
@@ -125,6 +133,25 @@ impl PartialEq for Span {
 }
 impl Eq for Span {}
 
+/// Spans are ordered by `base`, then by `acme`, so sorted spans are in address order.
+///
+/// Consistent with [`PartialEq`], all empty spans are equal, and order before any sized span.
+impl Ord for Span {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        match (self.is_empty(), other.is_empty()) {
+            (true, true) => core::cmp::Ordering::Equal,
+            (true, false) => core::cmp::Ordering::Less,
+            (false, true) => core::cmp::Ordering::Greater,
+            (false, false) => (self.base, self.acme).cmp(&(other.base, other.acme)),
+        }
+    }
+}
+impl PartialOrd for Span {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Span {
     /// Returns whether `base >= acme`.
     #[inline]
@@ -138,7 +165,11 @@ impl Span {
         !self.is_empty()
     }
 
-    /// Returns the size of the span, else zero if `base >= span`.
+    /// Returns the size of the span, else zero if `base >= acme`.
+    ///
+    /// This never panics nor wraps around, even for spans containing the null address
+    /// or ending at the top of the address space. [`Span::empty()`], single-point spans
+    /// (where `base == acme`), and inverted spans (where `base > acme`) all have a size of zero.
     #[inline]
     pub fn size(self) -> usize {
         if self.is_empty() { 0 } else { self.acme as usize - self.base as usize }
@@ -165,6 +196,26 @@ impl Span {
     /// Creates a [`Span`] given a `base` and a `size`.
     ///
     /// If `base + size` overflows, the result is empty.
+    ///
+    /// Like [`Span::new`], this can be used in `const` and `static` initializers.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use talc::*;
+    /// use core::ptr::addr_of_mut;
+    ///
+    /// const ARENA_SIZE: usize = 10000;
+    /// static mut ARENA: [u8; ARENA_SIZE] = [0; ARENA_SIZE];
+    ///
+    /// static ALLOCATOR: Talck<spin::Mutex<()>, ClaimOnOom> = Talc::new(unsafe {
+    ///     ClaimOnOom::new(Span::from_base_size(addr_of_mut!(ARENA).cast(), ARENA_SIZE))
+    /// })
+    /// .lock();
+    ///
+    /// let layout = core::alloc::Layout::new::<u64>();
+    /// let ptr = unsafe { ALLOCATOR.lock().malloc(layout).unwrap() };
+    /// unsafe { ALLOCATOR.lock().free(ptr, layout) };
+    /// ```
     #[inline]
     pub const fn from_base_size(base: *mut u8, size: usize) -> Self {
         Self { base, acme: base.wrapping_add(size) }
@@ -236,6 +287,24 @@ impl Span {
         }
     }
 
+    /// Returns a mutable slice of the span's memory. Empty spans yield an empty slice.
+    ///
+    /// This is useful for initializing memory, e.g. writing a poison pattern or pre-faulting
+    /// pages, before handing it to the allocator.
+    ///
+    /// # Safety
+    /// The slice aliases everything in the span. For the lifetime `'a`, the span's memory
+    /// must be valid for reads and writes, and must not be accessed through any other
+    /// pointer or reference. This includes accesses by a [`Talc`](crate::Talc)
+    /// that's claimed any of it, and to any allocations within it.
+    #[inline]
+    pub unsafe fn as_uninit_slice_mut<'a>(self) -> &'a mut [MaybeUninit<u8>] {
+        match self.to_slice() {
+            Some(slice) => &mut *(slice as *mut [MaybeUninit<u8>]),
+            None => &mut [],
+        }
+    }
+
     /// Returns whether `self` contains `addr`.
     ///
     /// Empty spans contain nothing.
@@ -262,6 +331,10 @@ impl Span {
     }
 
     /// Aligns `base` upward and `acme` downward by `align_of::<usize>()`.
+    ///
+    /// Each end moves by less than [`ALIGN`], so the result loses less than `2 * ALIGN` bytes.
+    /// A span of at least `2 * ALIGN - 1` bytes is therefore never emptied, while a span
+    /// of `ALIGN` bytes or less is emptied unless its `base` is already aligned.
     #[inline]
     pub fn word_align_inward(self) -> Self {
         if ALIGN > usize::MAX - self.base as usize {
@@ -291,9 +364,34 @@ impl Span {
         Self { base: self.base, acme: if max < self.acme { max } else { self.acme } }
     }
 
+    /// Returns the midpoint of `self`, aligned down to [`ALIGN`], e.g. to split it in two.
+    ///
+    /// If `base` isn't aligned, the result can be below it, see
+    /// [`word_align_inward`](Span::word_align_inward). Returns `base` if `self` is empty.
+    #[inline]
+    pub fn mid(self) -> *mut u8 {
+        align_down(self.base.wrapping_add(self.size() / 2))
+    }
+
+    /// Clamps `ptr` into `base..=acme`.
+    ///
+    /// This is useful for keeping a boundary computed from untrusted input, e.g. for
+    /// [`Talc::truncate`](crate::Talc::truncate), within a heap.
+    /// Returns `base` if `self` is empty.
+    #[inline]
+    pub fn clamp_ptr(self, ptr: *mut u8) -> *mut u8 {
+        if self.is_empty() || ptr < self.base {
+            self.base
+        } else if ptr > self.acme {
+            self.acme
+        } else {
+            ptr
+        }
+    }
+
     /// Returns the [`Span`]s of `self` below and above the `exclude` span, respectively.
     /// Alternatively worded, the set difference `self`\\`exclude`.
-    /// 
+    ///
     /// If `exclude` is empty, `self` and an empty `Span` are returned.
     #[inline]
     pub fn except(self, exclude: Span) -> (Self, Self) {
@@ -317,6 +415,65 @@ impl Span {
             }
         }
     }
+
+    /// Returns the intersection of `self` and `other`.
+    ///
+    /// If the spans don't overlap, the result is [`Span::empty()`].
+    ///
+    /// This is equivalent to [`fit_within`](Span::fit_within), except that
+    /// an empty result is always normalized to [`Span::empty()`].
+    #[inline]
+    pub fn intersection(self, other: Span) -> Self {
+        if self.overlaps(other) {
+            Self {
+                base: if other.base > self.base { other.base } else { self.base },
+                acme: if other.acme < self.acme { other.acme } else { self.acme },
+            }
+        } else {
+            Self::empty()
+        }
+    }
+
+    /// Returns the part of `self` within `parent`, i.e. the intersection.
+    ///
+    /// This is useful for ensuring that a span, such as an extended heap,
+    /// never exceeds the memory region it's allowed to occupy.
+    #[inline]
+    pub fn clamp_to(self, parent: Span) -> Self {
+        self.intersection(parent)
+    }
+
+    /// Returns an iterator over the `page_size`-aligned pages fully contained by `self`.
+    ///
+    /// Any partial pages at either end of `self` are skipped.
+    ///
+    /// # Panics
+    /// Panics if `page_size` is not a power of two.
+    pub fn pages(self, page_size: usize) -> impl Iterator<Item = Span> {
+        assert!(page_size.is_power_of_two(), "page_size must be a power of two");
+
+        let page_mask = page_size - 1;
+
+        let (first_page, page_count) = match self.get_base_acme() {
+            Some((base, acme)) if page_mask <= usize::MAX - base as usize => {
+                let first_page = align_up_by(base, page_mask);
+                let pages_acme = acme as usize & !page_mask;
+
+                let page_count = if pages_acme > first_page as usize {
+                    (pages_acme - first_page as usize) / page_size
+                } else {
+                    0
+                };
+
+                (first_page, page_count)
+            }
+            _ => (core::ptr::null_mut(), 0),
+        };
+
+        (0..page_count)
+            .map(move |i| Span::from_base_size(first_page.wrapping_add(i * page_size), page_size))
+    }
+
     /// Returns a span that contains `other` by extending `self`.
     ///
     /// If `other` is empty, returns `self`, as all spans contain any empty span.
@@ -350,6 +507,47 @@ impl Span {
         }
     }
 
+    /// Lower `base` by `by`, saturating at the bottom of the address space.
+    ///
+    /// Does nothing if `self` is empty.
+    #[inline]
+    pub fn extend_low(self, by: usize) -> Self {
+        if self.is_empty() {
+            self
+        } else {
+            Self { base: self.base.wrapping_sub(by.min(self.base as usize)), acme: self.acme }
+        }
+    }
+
+    /// Raise `acme` by `by`, saturating at the top of the address space.
+    ///
+    /// Does nothing if `self` is empty.
+    #[inline]
+    pub fn extend_high(self, by: usize) -> Self {
+        if self.is_empty() {
+            self
+        } else {
+            let by = by.min(usize::MAX - self.acme as usize);
+            Self { base: self.base, acme: self.acme.wrapping_add(by) }
+        }
+    }
+
+    /// Raise `base` by `by`, saturating at `acme`, which empties the span.
+    ///
+    /// Does nothing if `self` is empty.
+    #[inline]
+    pub fn truncate_low(self, by: usize) -> Self {
+        Self { base: self.base.wrapping_add(by.min(self.size())), acme: self.acme }
+    }
+
+    /// Lower `acme` by `by`, saturating at `base`, which empties the span.
+    ///
+    /// Does nothing if `self` is empty.
+    #[inline]
+    pub fn truncate_high(self, by: usize) -> Self {
+        Self { base: self.base, acme: self.acme.wrapping_sub(by.min(self.size())) }
+    }
+
     /// Raise `base` by `low` and lower `acme` by `high`.
     ///
     /// If `self` is empty, `self` is returned.
@@ -442,4 +640,202 @@ mod test {
         assert!(span.truncate(1234, 1010) == Span::new(ptr(1234 + 1234), ptr(5678 - 1010)));
         assert!(span.truncate(235623, 45235772).is_empty());
     }
+
+    #[test]
+    fn test_span_ptr_range() {
+        let range = ptr(1234)..ptr(5678);
+        assert_eq!(Span::from(range.clone()).to_ptr_range(), Some(range));
+
+        // inverted and single-point ranges are empty
+        assert!(Span::from(ptr(5678)..ptr(1234)).is_empty());
+        assert_eq!(Span::from(ptr(5678)..ptr(1234)).to_ptr_range(), None);
+        assert_eq!(Span::from(ptr(1234)..ptr(1234)).to_ptr_range(), None);
+    }
+
+    #[test]
+    fn test_span_mid_and_clamp_ptr() {
+        let span = Span::from_base_size(ptr(ALIGN * 100), ALIGN * 10 + 1);
+        assert_eq!(span.mid(), ptr(ALIGN * 105));
+        assert_eq!(Span::from_base_size(ptr(ALIGN * 100), ALIGN * 3).mid(), ptr(ALIGN * 101));
+        assert_eq!(Span::empty().mid(), ptr(0));
+
+        let (base, acme) = span.get_base_acme().unwrap();
+        assert_eq!(span.clamp_ptr(ptr(0)), base);
+        assert_eq!(span.clamp_ptr(span.mid()), span.mid());
+        assert_eq!(span.clamp_ptr(acme), acme);
+        assert_eq!(span.clamp_ptr(ptr(usize::MAX)), acme);
+
+        let inverted = Span::new(acme, base);
+        assert_eq!(inverted.clamp_ptr(ptr(usize::MAX)), acme);
+        assert_eq!(inverted.clamp_ptr(span.mid()), acme);
+    }
+
+    #[test]
+    fn test_span_ord() {
+        let low = Span::new(ptr(100), ptr(200));
+        let low_wide = Span::new(ptr(100), ptr(300));
+        let high = Span::new(ptr(150), ptr(160));
+        let empty = Span::new(ptr(500), ptr(400));
+
+        let mut spans = [high, empty, low_wide, low, Span::empty()];
+        spans.sort();
+        assert_eq!(spans, [Span::empty(), empty, low, low_wide, high]);
+
+        assert!(empty == Span::empty() && empty.cmp(&Span::empty()).is_eq());
+        assert!(low < high && low < low_wide);
+
+        let set = std::collections::BTreeSet::from(spans);
+        assert!(set.iter().copied().eq([Span::empty(), low, low_wide, high]));
+    }
+
+    #[test]
+    fn test_span_intersection() {
+        let span = Span::new(ptr(1234), ptr(5678));
+
+        assert_eq!(span.intersection(span), span);
+        assert_eq!(span.intersection(Span::new(ptr(0), ptr(10000))), span);
+        assert_eq!(
+            span.intersection(Span::new(ptr(2345), ptr(3456))),
+            Span::new(ptr(2345), ptr(3456))
+        );
+        assert_eq!(
+            span.intersection(Span::new(ptr(4000), ptr(10000))),
+            Span::new(ptr(4000), ptr(5678))
+        );
+        assert_eq!(
+            span.intersection(Span::new(ptr(0), ptr(4000))),
+            Span::new(ptr(1234), ptr(4000))
+        );
+
+        // disjoint and adjacent spans
+        assert!(span.intersection(Span::new(ptr(6000), ptr(7000))).is_empty());
+        assert!(span.intersection(Span::new(ptr(5678), ptr(7000))).is_empty());
+        assert!(span.intersection(Span::new(ptr(0), ptr(1234))).is_empty());
+        assert!(span.intersection(Span::empty()).is_empty());
+        assert!(Span::empty().intersection(span).is_empty());
+
+        let parent = Span::new(ptr(1000), ptr(6000));
+        assert_eq!(span.extend(500, 500).clamp_to(parent), Span::new(ptr(1000), ptr(6000)));
+        assert_eq!(span.extend(100, 100).clamp_to(parent), span.extend(100, 100));
+    }
+
+    #[test]
+    fn test_span_pages() {
+        let span = Span::new(ptr(0x1234), ptr(0x5678));
+        let pages = span.pages(0x1000).collect::<std::vec::Vec<_>>();
+
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[0], Span::new(ptr(0x2000), ptr(0x3000)));
+        assert_eq!(pages[2], Span::new(ptr(0x4000), ptr(0x5000)));
+
+        // exactly aligned spans include their boundary pages
+        assert_eq!(Span::new(ptr(0x2000), ptr(0x4000)).pages(0x1000).count(), 2);
+
+        // spans not containing a whole page
+        assert_eq!(Span::new(ptr(0x1001), ptr(0x2fff)).pages(0x1000).count(), 0);
+        assert_eq!(Span::new(ptr(0x1800), ptr(0x2800)).pages(0x1000).count(), 0);
+        assert_eq!(Span::empty().pages(0x1000).count(), 0);
+        assert_eq!(Span::new(ptr(usize::MAX - 0x10), ptr(usize::MAX)).pages(0x1000).count(), 0);
+    }
+
+    #[test]
+    fn test_span_repr() {
+        assert_eq!(core::mem::size_of::<Span>(), 2 * core::mem::size_of::<*mut u8>());
+        assert_eq!(core::mem::align_of::<Span>(), core::mem::align_of::<*mut u8>());
+
+        let span = Span::new(ptr(0x1234), ptr(0x5678));
+        let [base, acme] = unsafe { core::mem::transmute::<Span, [*mut u8; 2]>(span) };
+        assert_eq!((base, acme), (ptr(0x1234), ptr(0x5678)));
+    }
+
+    #[test]
+    fn test_span_as_uninit_slice_mut() {
+        let mut arena = [0u8; 100];
+        let span = Span::from(&mut arena).truncate(10, 10);
+
+        let slice = unsafe { span.as_uninit_slice_mut() };
+        assert_eq!(slice.len(), 80);
+        slice.fill(MaybeUninit::new(0xcd));
+
+        assert!(arena[..10].iter().chain(&arena[90..]).all(|&b| b == 0));
+        assert!(arena[10..90].iter().all(|&b| b == 0xcd));
+
+        assert!(unsafe { Span::empty().as_uninit_slice_mut() }.is_empty());
+    }
+
+    #[test]
+    fn test_span_one_sided() {
+        let span = Span::new(ptr(1234), ptr(5678));
+
+        assert_eq!(span.extend_low(234), Span::new(ptr(1000), ptr(5678)));
+        assert_eq!(span.extend_high(322), Span::new(ptr(1234), ptr(6000)));
+        assert_eq!(span.truncate_low(234), Span::new(ptr(1468), ptr(5678)));
+        assert_eq!(span.truncate_high(678), Span::new(ptr(1234), ptr(5000)));
+
+        // saturate at the edges of the address space
+        assert_eq!(span.extend_low(usize::MAX), Span::new(ptr(0), ptr(5678)));
+        assert_eq!(span.extend_high(usize::MAX), Span::new(ptr(1234), ptr(usize::MAX)));
+
+        // saturate at the other boundary
+        assert!(span.truncate_low(usize::MAX).is_empty());
+        assert!(span.truncate_high(4444).is_empty());
+        assert_eq!(span.truncate_high(4443).size(), 1);
+
+        // empty spans are left alone
+        let empty = Span::new(ptr(5678), ptr(1234));
+        assert_eq!(empty.extend_low(10), empty);
+        assert_eq!(empty.extend_high(10), empty);
+        assert_eq!(empty.truncate_low(10), empty);
+        assert_eq!(empty.truncate_high(10), empty);
+    }
+
+    #[test]
+    fn test_span_word_align_inward() {
+        let aligned = ALIGN * 1000;
+
+        // an aligned span is left alone
+        let span = Span::from_base_size(ptr(aligned), ALIGN);
+        assert_eq!(span.word_align_inward(), span);
+
+        let expected = Span::from_base_size(ptr(aligned + ALIGN), ALIGN);
+
+        for offset in 1..ALIGN {
+            let base = ptr(aligned + offset);
+
+            // ALIGN bytes that aren't aligned are emptied
+            assert!(Span::from_base_size(base, ALIGN).word_align_inward().is_empty());
+
+            // one more byte only survives if it reaches the next aligned address
+            let span = Span::from_base_size(base, ALIGN + 1).word_align_inward();
+            assert_eq!(span.is_empty(), offset != ALIGN - 1);
+
+            // while 2 * ALIGN - 1 bytes always survive
+            assert_eq!(Span::from_base_size(base, 2 * ALIGN - 1).word_align_inward(), expected);
+        }
+    }
+
+    #[test]
+    fn test_span_size() {
+        assert_eq!(Span::empty().size(), 0);
+        assert_eq!(Span::default().size(), 0);
+
+        // single-point spans
+        assert_eq!(Span::new(ptr(1234), ptr(1234)).size(), 0);
+        assert_eq!(Span::new(ptr(usize::MAX), ptr(usize::MAX)).size(), 0);
+
+        // inverted spans
+        assert_eq!(Span::new(ptr(5678), ptr(1234)).size(), 0);
+        assert_eq!(Span::new(ptr(usize::MAX), ptr(0)).size(), 0);
+
+        // spans containing the null address
+        assert_eq!(Span::new(ptr(0), ptr(1234)).size(), 1234);
+        assert_eq!(Span::new(ptr(0), ptr(usize::MAX)).size(), usize::MAX);
+
+        // spans at the top of the address space
+        assert_eq!(Span::new(ptr(usize::MAX - 1234), ptr(usize::MAX)).size(), 1234);
+        assert_eq!(Span::from_base_size(ptr(usize::MAX - 1234), 1234).size(), 1234);
+
+        // wrapping around the address space results in an empty span
+        assert_eq!(Span::from_base_size(ptr(usize::MAX - 1234), 1235).size(), 0);
+    }
 }
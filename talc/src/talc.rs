@@ -20,6 +20,8 @@ const MIN_CHUNK_SIZE: usize = MIN_TAG_OFFSET + TAG_SIZE;
 const MIN_HEAP_SIZE: usize = MIN_CHUNK_SIZE + TAG_SIZE;
 
 const BIN_COUNT: usize = usize::BITS as usize * 2;
+// each bin needs a flag in `availability_low` or `availability_high`
+const _: () = assert!(BIN_COUNT <= 2 * WORD_BITS, "not enough availability flags for every bin");
 
 type Bin = Option<NonNull<LlistNode>>;
 
@@ -155,6 +157,9 @@ unsafe fn bin_of_size(size: usize) -> usize {
     /// Log 2 of (minimum pseudo-exponential chunk size)
     const MIN_EXP_BITS_LESS_ONE: usize = DOUBLE_BIN_LIMIT.ilog2() as usize;
 
+    // sizes beyond the last bin are capped to it, but the linear bins can't be
+    const _: () = assert!(EXP_BUCKET < BIN_COUNT, "the binning parameters require too many bins");
+
     debug_assert!(size >= MIN_CHUNK_SIZE);
 
     if size < WORD_BIN_LIMIT {
@@ -200,6 +205,51 @@ unsafe fn bin_of_size(size: usize) -> usize {
 /// [`GlobalAlloc`](core::alloc::GlobalAlloc) and [`Allocator`](core::alloc::Allocator) traits.
 ///
 /// Check out the associated functions `new`, `claim`, `lock`, `extend`, and `truncate`.
+///
+/// # Determinism
+/// Talc's behavior depends only on the sequence of operations performed on it and the
+/// addresses of the memory it's given. Given the same sequence of operations on heaps
+/// claimed at addresses that are equal modulo the largest alignment requested, the returned
+/// pointers are always at the same offsets from their heap's base. Thus, in tests, pointer
+/// offsets from a fixed arena are reproducible regardless of where the arena ends up.
+///
+/// # Binning
+/// The bins free memory is sorted into are fixed: [`BIN_COUNT`](Talc::BIN_COUNT) and the
+/// size of each bin only depend on the word size, and the bin list is laid out in the
+/// metadata accordingly. How bins are searched is selected at compile time by the
+/// `"best-fit"` and `"next-fit"` features. Neither can be changed on a live allocator.
+///
+/// To start over with different settings, e.g. [`set_min_split_size`](Talc::set_min_split_size),
+/// free every allocation, [`reset`](Talc::reset), then claim the same memory again:
+/// ```rust
+/// # use talc::*;
+/// # use core::alloc::Layout;
+/// let mut arena = [0u8; 10000];
+/// let mut talc = Talc::new(ErrOnOom);
+/// let layout = Layout::new::<[u64; 4]>();
+///
+/// unsafe {
+///     talc.claim(arena.as_mut().into()).unwrap();
+///     let ptr = talc.malloc(layout).unwrap();
+///     talc.free(ptr, layout);
+///
+///     // all allocations are freed, so the heap can be forgotten and rebuilt
+///     talc.reset();
+///     talc.set_min_split_size(256);
+///     talc.claim(arena.as_mut().into()).unwrap();
+///     let ptr = talc.malloc(layout).unwrap();
+///     talc.free(ptr, layout);
+/// }
+/// ```
+///
+/// # Priming
+/// Bins can't be pre-populated with gaps of chosen sizes. Adjacent free memory is always
+/// merged into a single gap, and every gap must sit directly above an allocated chunk's tag,
+/// so a claimed heap can't be carved into several free chunks without leaving allocations
+/// between them. Nor does it need to be: allocating from a fresh heap takes the front of its
+/// one large gap, found by inspecting a single bin, so the first burst of allocations is
+/// already as fast as it gets, and packs them together. Enable the `"scan-metrics"` feature
+/// and check `average_bins_scanned` to confirm this for a workload.
 pub struct Talc<O: OomHandler> {
     /// The low bits of the availability flags.
     availability_low: usize,
@@ -208,6 +258,23 @@ pub struct Talc<O: OomHandler> {
     /// Linked list heads.
     bins: *mut Bin,
 
+    /// The smallest leftover that's split off into a new gap, see
+    /// [`set_min_split_size`](Talc::set_min_split_size).
+    min_split_size: usize,
+    /// Covers every heap claimed so far, see [`claimed_extent`](Talc::claimed_extent).
+    claimed_extent: Span,
+    /// The OOM handler isn't invoked if this would be exceeded, see
+    /// [`set_max_claimed_bytes`](Talc::set_max_claimed_bytes).
+    #[cfg(feature = "counters")]
+    max_claimed_bytes: usize,
+    /// The gap following the last one allocated from by [`next_fit_in_bin`](Talc::next_fit_in_bin).
+    #[cfg(feature = "next-fit")]
+    next_fit_cursor: Option<NonNull<LlistNode>>,
+    /// The last allocation freed, kept for `malloc` to reuse, see
+    /// [`flush_hot_cache`](Talc::flush_hot_cache).
+    #[cfg(feature = "hot-cache")]
+    hot_cache: Option<(NonNull<u8>, Layout)>,
+
     /// The user-specified OOM handler.
     ///
     /// Its state is entirely maintained by the user.
@@ -216,6 +283,17 @@ pub struct Talc<O: OomHandler> {
     #[cfg(feature = "counters")]
     /// Allocation stats.
     counters: counters::Counters,
+
+    #[cfg(feature = "histogram")]
+    /// Allocation counts per bin, see [`size_histogram`](Talc::size_histogram).
+    size_histogram: [usize; BIN_COUNT],
+
+    #[cfg(feature = "scan-metrics")]
+    /// The number of searches for a gap, see [`average_bins_scanned`](Talc::average_bins_scanned).
+    gap_searches: u64,
+    #[cfg(feature = "scan-metrics")]
+    /// The number of bins those searches scanned.
+    bins_scanned: u64,
 }
 
 unsafe impl<O: Send + OomHandler> Send for Talc<O> {}
@@ -226,11 +304,23 @@ impl<O: OomHandler> core::fmt::Debug for Talc<O> {
             .field("availability_low", &format_args!("{:x}", self.availability_low))
             .field("availability_high", &format_args!("{:x}", self.availability_high))
             .field("metadata_ptr", &self.bins)
+            .field("min_split_size", &self.min_split_size)
             .finish()
     }
 }
 
 impl<O: OomHandler> Talc<O> {
+    /// The number of bins free memory is sorted into. See [`bin_stats`](Talc::bin_stats).
+    pub const BIN_COUNT: usize = BIN_COUNT;
+
+    /// The number of bytes of metadata, see [`claim_with_metadata`](Talc::claim_with_metadata).
+    pub const METADATA_SIZE: usize = core::mem::size_of::<Bin>() * BIN_COUNT;
+
+    /// The minimum size of the first heap [`claim`](Talc::claim)ed, after being
+    /// word-aligned inward, as the metadata is placed in it.
+    /// See also [`establish_metadata`](Talc::establish_metadata).
+    pub const MIN_FIRST_HEAP_SIZE: usize = TAG_SIZE + Self::METADATA_SIZE + TAG_SIZE;
+
     #[inline]
     const fn required_chunk_size(size: usize) -> usize {
         if size <= MIN_CHUNK_SIZE - TAG_SIZE {
@@ -240,6 +330,13 @@ impl<O: OomHandler> Talc<O> {
         }
     }
 
+    /// Returns whether the two pointers are far enough apart to be worth splitting off as a gap.
+    #[inline]
+    fn is_split_size(&self, base: *mut u8, acme: *mut u8) -> bool {
+        debug_assert!(acme >= base, "!(acme {:p} >= base {:p})", acme, base);
+        acme as usize - base as usize >= self.min_split_size
+    }
+
     /// Get the pointer to the `bin`th bin.
     /// # Safety
     /// `bin` must be smaller than `BIN_COUNT`.
@@ -314,7 +411,15 @@ impl<O: OomHandler> Talc<O> {
         #[cfg(feature = "counters")]
         self.counters.account_deregister_gap(gap_base_to_size(base).read());
 
-        LlistNode::remove(gap_base_to_node(base));
+        let node = gap_base_to_node(base);
+
+        // don't leave the cursor dangling, move it along instead
+        #[cfg(feature = "next-fit")]
+        if self.next_fit_cursor == Some(NonNull::new_unchecked(node)) {
+            self.next_fit_cursor = (*node).next;
+        }
+
+        LlistNode::remove(node);
 
         if (*self.get_bin_ptr(bin)).is_none() {
             self.clear_avails(bin);
@@ -322,20 +427,108 @@ impl<O: OomHandler> Talc<O> {
     }
 
     /// Allocate a contiguous region of memory according to `layout`, if possible.
+    ///
+    /// Allocations are always aligned to at least [`ALIGN`](crate::ptr_utils::ALIGN),
+    /// the alignment of `usize`, even if `layout.align()` is smaller.
+    ///
     /// # Safety
     /// `layout.size()` must be nonzero.
+    #[must_use = "discarding the allocation leaks it"]
     pub unsafe fn malloc(&mut self, layout: Layout) -> Result<NonNull<u8>, ()> {
         debug_assert!(layout.size() != 0);
         self.scan_for_errors();
 
-        let (mut free_base, free_acme, alloc_base) = loop {
+        #[cfg(feature = "hot-cache")]
+        if let Some((ptr, cached)) = self.hot_cache.take() {
+            // the tag must be found in the same place, else the chunk is freed after all
+            if is_aligned_to(ptr.as_ptr(), layout.align())
+                && align_up(ptr.as_ptr().add(layout.size()))
+                    == align_up(ptr.as_ptr().add(cached.size()))
+            {
+                #[cfg(feature = "histogram")]
+                {
+                    let bin = bin_of_size(Self::required_chunk_size(layout.size()));
+                    self.size_histogram[bin] += 1;
+                }
+
+                #[cfg(feature = "counters")]
+                self.counters.account_alloc(layout.size());
+
+                return Ok(ptr);
+            }
+
+            self.free_chunk(ptr, cached.size());
+        }
+
+        let (free_base, free_acme, alloc_base) = loop {
             // this returns None if there are no heaps or allocatable memory
             match self.get_sufficient_chunk(layout) {
                 Some(payload) => break payload,
-                None => _ = O::handle_oom(self, layout)?,
+                None => {
+                    if self.invoke_oom_handler(layout).is_err() {
+                        // the lock may be held, so the logger mustn't allocate from this allocator
+                        #[cfg(feature = "log-oom")]
+                        log::warn!("talc: out of memory, failed to allocate {:?}", layout);
+
+                        return Err(());
+                    }
+                }
             }
         };
 
+        #[cfg(feature = "histogram")]
+        {
+            self.size_histogram[bin_of_size(Self::required_chunk_size(layout.size()))] += 1;
+        }
+
+        let ptr = self.allocate_chunk(free_base, free_acme, alloc_base, layout.size());
+
+        // the fast path of get_sufficient_chunk relies on gaps being ALIGN-aligned
+        // to satisfy small alignments, so make sure that holds
+        debug_assert!(is_aligned_to(ptr.as_ptr(), layout.align()));
+
+        Ok(ptr)
+    }
+
+    /// Allocate a contiguous region of memory according to `layout`, if possible.
+    ///
+    /// Returns `Err` with the [`largest_allocatable`](Talc::largest_allocatable) size for
+    /// `layout.align()` if allocation fails, even after invoking the OOM handler.
+    /// This is less than `layout.size()`, and zero if there's no memory available at all.
+    /// This allows telling a full heap apart from a fragmented one, e.g. to decide whether
+    /// to fall back to another allocator.
+    ///
+    /// # Safety
+    /// `layout.size()` must be nonzero.
+    #[must_use = "discarding the allocation leaks it"]
+    pub unsafe fn try_malloc(&mut self, layout: Layout) -> Result<NonNull<u8>, usize> {
+        self.malloc(layout).map_err(|()| self.largest_allocatable(layout.align()))
+    }
+
+    /// Like [`malloc`](Talc::malloc), but accepts zero-sized layouts, for which a dangling
+    /// pointer aligned to `layout.align()` is returned without allocating.
+    ///
+    /// Use [`free_or_dangling`](Talc::free_or_dangling) to free the result.
+    #[must_use = "discarding the allocation leaks it"]
+    pub fn malloc_or_dangling(&mut self, layout: Layout) -> Result<NonNull<u8>, ()> {
+        if layout.size() == 0 {
+            return Ok(dangling(layout.align()));
+        }
+
+        // SAFETY: the size is nonzero
+        unsafe { self.malloc(layout) }
+    }
+
+    /// Allocate `size` bytes at `alloc_base` within the deregistered free chunk,
+    /// registering the leftover memory on either side as gaps if large enough.
+    #[inline(always)]
+    unsafe fn allocate_chunk(
+        &mut self,
+        mut free_base: *mut u8,
+        free_acme: *mut u8,
+        alloc_base: *mut u8,
+        size: usize,
+    ) -> NonNull<u8> {
         // determine the base of the allocated chunk
         // if the amount of memory below the chunk is too small, subsume it, else free it
         let chunk_base_ceil = alloc_base.min(free_acme.sub(MIN_CHUNK_SIZE));
@@ -346,15 +539,20 @@ impl<O: OomHandler> Talc<O> {
             Tag::clear_above_free(free_base.sub(TAG_SIZE).cast());
         }
 
+        #[cfg(feature = "counters")]
+        if alloc_base != free_base {
+            self.counters.account_alignment_slack(alloc_base as usize - free_base as usize);
+        }
+
         // the word immediately after the allocation
-        let post_alloc_ptr = align_up(alloc_base.add(layout.size()));
+        let post_alloc_ptr = align_up(alloc_base.add(size));
         // the tag position, accounting for the minimum size of a chunk
         let mut tag_ptr = free_base.add(MIN_TAG_OFFSET).max(post_alloc_ptr);
         // the pointer after the lowest possible tag pointer
         let min_alloc_chunk_acme = tag_ptr.add(TAG_SIZE);
 
         // handle the space above the required allocation span
-        if is_chunk_size(min_alloc_chunk_acme, free_acme) {
+        if self.is_split_size(min_alloc_chunk_acme, free_acme) {
             self.register_gap(min_alloc_chunk_acme, free_acme);
             Tag::write(tag_ptr.cast(), free_base, true);
         } else {
@@ -368,9 +566,157 @@ impl<O: OomHandler> Talc<O> {
         }
 
         #[cfg(feature = "counters")]
-        self.counters.account_alloc(layout.size());
+        self.counters.account_alloc(size);
+
+        NonNull::new_unchecked(alloc_base)
+    }
+
+    /// Ensure that a subsequent allocation of `layout` can be serviced from the memory
+    /// that's already been claimed, invoking the OOM handler if necessary.
+    ///
+    /// No memory is allocated. This is useful for front-loading the cost of the
+    /// OOM handler (e.g. acquiring memory from the OS) ahead of latency-sensitive code.
+    ///
+    /// Returns whether a subsequent [`malloc`](Talc::malloc) of `layout` is guaranteed to
+    /// succeed without invoking the OOM handler, assuming no intervening heap changes.
+    #[must_use = "reserving may have failed, in which case malloc may still invoke the OOM handler"]
+    pub fn reserve(&mut self, layout: Layout) -> bool {
+        self.scan_for_errors();
+        self.flush_hot_cache();
+
+        loop {
+            // SAFETY: the Talc's invariants are upheld, so the gap lists are sound
+            if unsafe { self.has_sufficient_chunk(layout) } {
+                return true;
+            }
+
+            if self.invoke_oom_handler(layout).is_err() {
+                return false;
+            }
+        }
+    }
+
+    /// Call the OOM handler. In debug builds, this panics if the handler returns `Ok`
+    /// without making any more memory available, as the caller would loop forever.
+    ///
+    /// With the `"counters"` feature, the handler isn't invoked if this would exceed
+    /// the [`max_claimed_bytes`](Talc::max_claimed_bytes).
+    ///
+    /// The handler isn't invoked if [`O::NEVER_HANDLES_OOM`](OomHandler::NEVER_HANDLES_OOM).
+    fn invoke_oom_handler(&mut self, layout: Layout) -> Result<(), ()> {
+        #[cfg(debug_assertions)]
+        let free_bytes = self.free_bytes();
+
+        #[cfg(feature = "counters")]
+        let exceeds_cap =
+            self.counters.claimed_bytes.saturating_add(layout.size()) > self.max_claimed_bytes;
+        #[cfg(not(feature = "counters"))]
+        let exceeds_cap = false;
+
+        if O::NEVER_HANDLES_OOM || exceeds_cap || O::handle_oom(self, layout).is_err() {
+            return Err(());
+        }
+
+        // the handler may have freed allocations to recover memory
+        self.flush_hot_cache();
+
+        #[cfg(debug_assertions)]
+        assert!(
+            self.free_bytes() > free_bytes,
+            "the OOM handler returned Ok but provided no memory"
+        );
+
+        Ok(())
+    }
+
+    /// Sum the sizes of all the gaps. Without the counters, this walks every gap.
+    #[cfg(debug_assertions)]
+    fn free_bytes(&self) -> usize {
+        #[cfg(feature = "counters")]
+        {
+            self.counters.available_bytes
+        }
+
+        #[cfg(not(feature = "counters"))]
+        {
+            let mut free_bytes = 0;
+
+            let mut next_bin = self.next_available_bin(0);
+            while let Some(bin) = next_bin {
+                // SAFETY: the bin is available, so the bins are initialized
+                for node_ptr in unsafe { LlistNode::iter_mut(*self.get_bin_ptr(bin)) } {
+                    free_bytes += unsafe { gap_node_to_size(node_ptr).read() };
+                }
+
+                next_bin = self.next_available_bin(bin + 1);
+            }
+
+            free_bytes
+        }
+    }
+
+    /// Allocate `total_size` bytes as any number of discontiguous segments.
+    ///
+    /// Each segment takes the whole of the first gap listed in the highest non-empty bin,
+    /// which isn't necessarily the largest gap, except for the last, which only takes what's
+    /// left. Each is passed to `segment` as `(ptr, len)` once allocated.
+    /// This is useful for scatter-gather structures, such as IO vectors and ring buffers,
+    /// that don't need the memory to be contiguous.
+    ///
+    /// Segments are [`ALIGN`](crate::ptr_utils::ALIGN)-aligned. Each one must be freed individually,
+    /// e.g. with [`free`](Talc::free) given `Layout::from_size_align(len, 1)`.
+    ///
+    /// Returns `Err` without allocating anything if the free memory is insufficient.
+    /// The OOM handler is not invoked.
+    ///
+    /// # Safety
+    /// `total_size` must be nonzero.
+    pub unsafe fn malloc_segmented(
+        &mut self,
+        total_size: usize,
+        mut segment: impl FnMut(NonNull<u8>, usize),
+    ) -> Result<(), ()> {
+        debug_assert!(total_size != 0);
+        self.scan_for_errors();
+        self.flush_hot_cache();
+
+        // check that there's enough memory before allocating anything
+        let mut capacity = 0;
+        let mut bin = self.next_available_bin(0).ok_or(())?;
+        'outer: loop {
+            for node_ptr in LlistNode::iter_mut(*self.get_bin_ptr(bin)) {
+                capacity += gap_node_to_size(node_ptr).read() - TAG_SIZE;
+
+                if capacity >= total_size {
+                    break 'outer;
+                }
+            }
+
+            bin = self.next_available_bin(bin + 1).ok_or(())?;
+        }
+
+        let mut remaining = total_size;
+        while remaining != 0 {
+            let bin = if self.availability_high != 0 {
+                2 * WORD_BITS - 1 - self.availability_high.leading_zeros() as usize
+            } else {
+                WORD_BITS - 1 - self.availability_low.leading_zeros() as usize
+            };
+
+            let node_ptr = (*self.get_bin_ptr(bin)).unwrap_unchecked();
+            let base = gap_node_to_base(node_ptr);
+            let size = gap_node_to_size(node_ptr).read();
+            self.deregister_gap(base, bin);
+
+            let segment_size = remaining.min(size - TAG_SIZE);
+            let ptr = self.allocate_chunk(base, base.add(size), base, segment_size);
+            segment(ptr, segment_size);
 
-        Ok(NonNull::new_unchecked(alloc_base))
+            remaining -= segment_size;
+        }
+
+        self.scan_for_errors();
+        Ok(())
     }
 
     /// Returns `(chunk_base, chunk_acme, alloc_base)`
@@ -380,8 +726,30 @@ impl<O: OomHandler> Talc<O> {
     ) -> Option<(*mut u8, *mut u8, *mut u8)> {
         let required_chunk_size = Self::required_chunk_size(layout.size());
 
+        #[cfg(feature = "scan-metrics")]
+        {
+            self.gap_searches += 1;
+        }
+
         // if there are no valid heaps, availability is zero, and next_available_bin returns None
-        let mut bin = self.next_available_bin(bin_of_size(required_chunk_size))?;
+        let mut bin = self.next_bin_to_scan(bin_of_size(required_chunk_size))?;
+
+        // gaps in the first bin may be smaller or larger than required, so look for the
+        // best fit there. any gap in the larger bins is a good fit, so take the first one
+        #[cfg(feature = "best-fit")]
+        match self.best_fit_in_bin(bin, layout, required_chunk_size) {
+            Ok(chunk) => return Some(chunk),
+            // the whole bin was searched, so skip it
+            Err(true) => bin = self.next_bin_to_scan(bin + 1)?,
+            Err(false) => (),
+        }
+
+        // resume the search of the first bin where the last one left off
+        #[cfg(feature = "next-fit")]
+        match self.next_fit_in_bin(bin, layout, required_chunk_size) {
+            Some(chunk) => return Some(chunk),
+            None => bin = self.next_bin_to_scan(bin + 1)?,
+        }
 
         if layout.align() <= ALIGN {
             // the required alignment is most often the machine word size (or less)
@@ -398,7 +766,7 @@ impl<O: OomHandler> Talc<O> {
                     }
                 }
 
-                bin = self.next_available_bin(bin + 1)?;
+                bin = self.next_bin_to_scan(bin + 1)?;
             }
         } else {
             // a larger than word-size alignment is demanded
@@ -424,9 +792,153 @@ impl<O: OomHandler> Talc<O> {
                     }
                 }
 
-                bin = self.next_available_bin(bin + 1)?;
+                bin = self.next_bin_to_scan(bin + 1)?;
+            }
+        }
+    }
+
+    /// Returns whether `get_sufficient_chunk` would succeed, without modifying the gaps.
+    unsafe fn has_sufficient_chunk(&self, layout: Layout) -> bool {
+        let required_chunk_size = Self::required_chunk_size(layout.size());
+        let align_mask = layout.align().max(ALIGN) - 1;
+        let required_size = layout.size() + TAG_SIZE;
+
+        let mut bin = bin_of_size(required_chunk_size);
+
+        while let Some(next_bin) = self.next_available_bin(bin) {
+            for node_ptr in LlistNode::iter_mut(*self.get_bin_ptr(next_bin)) {
+                let size = gap_node_to_size(node_ptr).read();
+
+                if size >= required_chunk_size {
+                    let base = gap_node_to_base(node_ptr);
+
+                    if align_up_by(base, align_mask).add(required_size) <= base.add(size) {
+                        return true;
+                    }
+                }
+            }
+
+            bin = next_bin + 1;
+        }
+
+        false
+    }
+
+    /// Searches up to `BEST_FIT_SCAN_LIMIT` gaps in `bin` for the smallest gap that fits `layout`.
+    ///
+    /// Returns `(chunk_base, chunk_acme, alloc_base)` if successful, having deregistered the gap.
+    /// Otherwise, returns whether the whole bin was searched.
+    #[cfg(feature = "best-fit")]
+    unsafe fn best_fit_in_bin(
+        &mut self,
+        bin: usize,
+        layout: Layout,
+        required_chunk_size: usize,
+    ) -> Result<(*mut u8, *mut u8, *mut u8), bool> {
+        /// Bounds the cost of searching bins with many gaps.
+        const BEST_FIT_SCAN_LIMIT: usize = 16;
+
+        let align_mask = layout.align().max(ALIGN) - 1;
+        let required_size = layout.size() + TAG_SIZE;
+
+        // (chunk_base, chunk_size, alloc_base)
+        let mut best: Option<(*mut u8, usize, *mut u8)> = None;
+
+        for (i, node_ptr) in LlistNode::iter_mut(*self.get_bin_ptr(bin)).enumerate() {
+            if i == BEST_FIT_SCAN_LIMIT {
+                if best.is_none() {
+                    return Err(false);
+                }
+
+                break;
+            }
+
+            let size = gap_node_to_size(node_ptr).read();
+
+            if size >= required_chunk_size
+                && best.map_or(true, |(_, best_size, _)| size < best_size)
+            {
+                let base = gap_node_to_base(node_ptr);
+                let aligned_ptr = align_up_by(base, align_mask);
+
+                if aligned_ptr.add(required_size) <= base.add(size) {
+                    best = Some((base, size, aligned_ptr));
+
+                    // can't do any better than this
+                    if size == required_chunk_size {
+                        break;
+                    }
+                }
+            }
+        }
+
+        match best {
+            Some((base, size, alloc_base)) => {
+                self.deregister_gap(base, bin);
+                Ok((base, base.add(size), alloc_base))
+            }
+            None => Err(true),
+        }
+    }
+
+    /// Searches `bin` for a gap that fits `layout`, starting from the gap after the one
+    /// last allocated from, and wrapping around to the head of the bin.
+    ///
+    /// Alignment is checked here too, so repeated aligned allocations don't
+    /// recheck the gaps that failed to satisfy the alignment last time.
+    ///
+    /// Returns `(chunk_base, chunk_acme, alloc_base)` if successful, having deregistered the gap.
+    /// Otherwise, the whole bin was searched.
+    #[cfg(feature = "next-fit")]
+    unsafe fn next_fit_in_bin(
+        &mut self,
+        bin: usize,
+        layout: Layout,
+        required_chunk_size: usize,
+    ) -> Option<(*mut u8, *mut u8, *mut u8)> {
+        let align_mask = layout.align().max(ALIGN) - 1;
+        let required_size = layout.size() + TAG_SIZE;
+
+        let head = *self.get_bin_ptr(bin);
+
+        // the cursor may be in another bin, or have moved off the end of this one
+        let cursor =
+            self.next_fit_cursor.filter(|&node| bin_of_size(gap_node_to_size(node).read()) == bin);
+
+        // search from the cursor to the end of the bin, then from the head up to the cursor
+        let after_cursor = LlistNode::iter_mut(cursor);
+        let before_cursor =
+            LlistNode::iter_mut(head).take_while(|&node_ptr| Some(node_ptr) != cursor);
+
+        for node_ptr in after_cursor.chain(before_cursor) {
+            let size = gap_node_to_size(node_ptr).read();
+
+            if size >= required_chunk_size {
+                let base = gap_node_to_base(node_ptr);
+                let aligned_ptr = align_up_by(base, align_mask);
+
+                if aligned_ptr.add(required_size) <= base.add(size) {
+                    self.next_fit_cursor = (*node_ptr.as_ptr()).next;
+                    self.deregister_gap(base, bin);
+                    return Some((base, base.add(size), aligned_ptr));
+                }
             }
         }
+
+        None
+    }
+
+    /// Like `next_available_bin`, but counts the bin as scanned if there is one.
+    #[inline(always)]
+    fn next_bin_to_scan(&mut self, next_bin: usize) -> Option<usize> {
+        let bin = self.next_available_bin(next_bin);
+
+        #[cfg(feature = "scan-metrics")]
+        if bin.is_some() {
+            self.bins_scanned += 1;
+        }
+
+        bin
     }
 
     #[inline(always)]
@@ -458,6 +970,13 @@ impl<O: OomHandler> Talc<O> {
     }
 
     /// Free previously allocated/reallocated memory.
+    ///
+    /// The freed chunk is merged with any bordering gaps immediately, so no matter
+    /// the order in which allocations are freed, no fragments are left behind.
+    ///
+    /// With the `"hot-cache"` feature, the allocation is instead kept for the next `malloc`
+    /// to reuse, and the one kept previously is freed.
+    /// See [`flush_hot_cache`](Talc::flush_hot_cache).
     /// # Safety
     /// `ptr` must have been previously allocated given `layout`.
     pub unsafe fn free(&mut self, ptr: NonNull<u8>, layout: Layout) {
@@ -465,16 +984,35 @@ impl<O: OomHandler> Talc<O> {
         #[cfg(feature = "counters")]
         self.counters.account_dealloc(layout.size());
 
-        let (tag_ptr, tag) = tag_from_alloc_ptr(ptr.as_ptr(), layout.size());
+        // cache this allocation, freeing the previously cached one instead, if any
+        #[cfg(feature = "hot-cache")]
+        let Some((ptr, layout)) = self.hot_cache.replace((ptr, layout)) else {
+            return;
+        };
+
+        self.free_chunk(ptr, layout.size());
+    }
+
+    /// Free the chunk of the allocation of `size` bytes at `ptr`, merging it with the
+    /// gaps on either side, without updating the counters.
+    unsafe fn free_chunk(&mut self, ptr: NonNull<u8>, size: usize) {
+        let (tag_ptr, tag) = tag_from_alloc_ptr(ptr.as_ptr(), size);
         let mut chunk_base = tag.chunk_base();
         let mut chunk_acme = tag_ptr.add(TAG_SIZE);
 
         debug_assert!(tag.is_allocated());
         debug_assert!(is_chunk_size(chunk_base, chunk_acme));
+        debug_assert!(
+            chunk_base as usize % ALIGN == 0 && chunk_base <= ptr.as_ptr(),
+            "freed pointer {:p} has an implausible chunk base {:p}, was the heap truncated?",
+            ptr,
+            chunk_base
+        );
 
         // try recombine below
         if is_gap_below(chunk_base) {
             let (below_base, below_size) = gap_acme_to_base_size(chunk_base);
+            self.check_bordering_gap(below_base, below_size);
             self.deregister_gap(below_base, bin_of_size(below_size));
 
             chunk_base = below_base;
@@ -485,6 +1023,7 @@ impl<O: OomHandler> Talc<O> {
         // try recombine above
         if tag.is_above_free() {
             let above_size = gap_base_to_size(chunk_acme).read();
+            self.check_bordering_gap(chunk_acme, above_size);
             self.deregister_gap(chunk_acme, bin_of_size(above_size));
 
             chunk_acme = chunk_acme.add(above_size);
@@ -494,10 +1033,38 @@ impl<O: OomHandler> Talc<O> {
         self.register_gap(chunk_base, chunk_acme);
     }
 
+    /// Like [`free`](Talc::free), but does nothing given a zero-sized layout.
+    ///
+    /// # Safety
+    /// `ptr` must have been previously allocated given `layout`,
+    /// or `layout.size()` must be zero.
+    pub unsafe fn free_or_dangling(&mut self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() != 0 {
+            self.free(ptr, layout);
+        }
+    }
+
+    /// Free the allocation kept by the last [`free`](Talc::free), if it hasn't been reused.
+    ///
+    /// With the `"hot-cache"` feature, the last allocation freed is kept, so that a `malloc`
+    /// of a similar size can return it immediately, skipping the bins. Any other `malloc`
+    /// frees it first. Until then, it's not counted by
+    /// [`largest_allocatable`](Talc::largest_allocatable), and it's within the
+    /// [`get_allocated_span`](Talc::get_allocated_span). Call this beforehand if that matters.
+    /// Without the feature, this does nothing.
+    pub fn flush_hot_cache(&mut self) {
+        #[cfg(feature = "hot-cache")]
+        if let Some((ptr, layout)) = self.hot_cache.take() {
+            // SAFETY: the cached allocation was freed by the user and hasn't been reused
+            unsafe { self.free_chunk(ptr, layout.size()) };
+        }
+    }
+
     /// Grow a previously allocated/reallocated region of memory to `new_size`.
     /// # Safety
     /// `ptr` must have been previously allocated or reallocated given `layout`.
     /// `new_size` must be larger or equal to `layout.size()`.
+    #[must_use = "the allocation may have moved, and the old pointer is invalid if so"]
     pub unsafe fn grow(
         &mut self,
         ptr: NonNull<u8>,
@@ -506,18 +1073,44 @@ impl<O: OomHandler> Talc<O> {
     ) -> Result<NonNull<u8>, ()> {
         match self.grow_in_place(ptr, old_layout, new_size) {
             Err(_) => {
+                #[cfg(feature = "realloc-shift-down")]
+                if let Ok(allocation) = self.grow_shift_down(ptr, old_layout, new_size) {
+                    return Ok(allocation);
+                }
+
                 // grow in-place failed; reallocate the slow way
                 let new_layout = Layout::from_size_align_unchecked(new_size, old_layout.align());
                 let allocation = self.malloc(new_layout)?;
                 allocation.as_ptr().copy_from_nonoverlapping(ptr.as_ptr(), old_layout.size());
                 self.free(ptr, old_layout);
 
+                #[cfg(feature = "counters")]
+                self.counters.account_relocation();
+
                 Ok(allocation)
             }
             res => res,
         }
     }
 
+    /// Returns the number of bytes usable from `ptr` in the chunk backing the allocation.
+    ///
+    /// This is at least `layout.size()`, but may be larger due to alignment
+    /// and minimum chunk size padding.
+    ///
+    /// The extra bytes may hold allocator metadata, so they must not be written to
+    /// directly. Instead, call [`Talc::grow_in_place`] with a `new_size` of up to
+    /// the returned value, which is guaranteed to succeed without moving the allocation.
+    /// # Safety
+    /// `ptr` must have been previously allocated or reallocated given `layout`.
+    pub unsafe fn usable_size(&self, ptr: NonNull<u8>, layout: Layout) -> usize {
+        let (tag_ptr, tag) = tag_from_alloc_ptr(ptr.as_ptr(), layout.size());
+
+        debug_assert!(tag.is_allocated());
+
+        tag_ptr as usize - ptr.as_ptr() as usize
+    }
+
     /// Attempt to grow a previously allocated/reallocated region of memory to `new_size`.
     ///
     /// Returns `Err` if reallocation could not occur in-place.
@@ -525,12 +1118,32 @@ impl<O: OomHandler> Talc<O> {
     /// # Safety
     /// `ptr` must have been previously allocated or reallocated given `layout`.
     /// `new_size` must be larger or equal to `layout.size()`.
+    #[inline]
+    #[must_use = "growing in-place may have failed, in which case the allocation is unchanged"]
     pub unsafe fn grow_in_place(
         &mut self,
         ptr: NonNull<u8>,
         old_layout: Layout,
         new_size: usize,
     ) -> Result<NonNull<u8>, ()> {
+        self.try_grow_in_place(ptr, old_layout, new_size).map_err(|_| ())
+    }
+
+    /// Attempt to grow a previously allocated/reallocated region of memory to `new_size`.
+    ///
+    /// Returns `Err` with the largest size the allocation could've been grown to in-place
+    /// if reallocation could not occur in-place. This is at least `layout.size()`.
+    /// Ownership of the memory remains with the caller.
+    /// # Safety
+    /// `ptr` must have been previously allocated or reallocated given `layout`.
+    /// `new_size` must be larger or equal to `layout.size()`.
+    #[must_use = "growing in-place may have failed, in which case the allocation is unchanged"]
+    pub unsafe fn try_grow_in_place(
+        &mut self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<NonNull<u8>, usize> {
         debug_assert!(new_size >= old_layout.size());
         self.scan_for_errors();
 
@@ -573,16 +1186,19 @@ impl<O: OomHandler> Talc<O> {
 
         // otherwise, check if 1) is free 2) is large enough
         // because free chunks don't border free chunks, this needn't be recursive
+        let mut max_tag_ptr = tag_ptr;
+
         if tag.is_above_free() {
             let above_size = gap_base_to_size(acme).read();
             let above_tag_ptr = tag_ptr.add(above_size);
+            max_tag_ptr = above_tag_ptr;
 
             if new_tag_ptr <= above_tag_ptr {
                 self.deregister_gap(acme, bin_of_size(above_size));
 
                 // finally, determine if the remainder of the free block is big enough
                 // to be freed again, or if the entire region should be allocated
-                if is_chunk_size(new_tag_ptr, above_tag_ptr) {
+                if self.is_split_size(new_tag_ptr, above_tag_ptr) {
                     self.register_gap(new_tag_ptr.add(TAG_SIZE), above_tag_ptr.add(TAG_SIZE));
                     Tag::write(new_tag_ptr.cast(), base, true);
                 } else {
@@ -600,39 +1216,101 @@ impl<O: OomHandler> Talc<O> {
             }
         }
 
-        Err(())
+        // the tag can be moved up to max_tag_ptr, which is aligned
+        Err(max_tag_ptr as usize - ptr.as_ptr() as usize)
     }
 
-    /// Shrink a previously allocated/reallocated region of memory to `new_size`.
+    /// Attempt to grow a previously allocated/reallocated region of memory to `new_size`
+    /// by moving it down into the gap directly below it, as well as the gap above, if any.
     ///
-    /// This function is infallible given valid inputs, and the reallocation will always be
-    /// done in-place, maintaining the validity of the pointer.
+    /// This avoids touching memory outside of the allocation's immediate surroundings
+    /// when growing in-place fails. The contents are moved while `self` is borrowed.
     ///
+    /// Returns `Err` if the surrounding gaps aren't large enough.
     /// # Safety
-    /// - `ptr` must have been previously allocated or reallocated given `layout`.
-    /// - `new_size` must be smaller or equal to `layout.size()`.
-    /// - `new_size` should be nonzero.
-    pub unsafe fn shrink(&mut self, ptr: NonNull<u8>, layout: Layout, new_size: usize) {
-        debug_assert!(new_size != 0);
-        debug_assert!(new_size <= layout.size());
+    /// `ptr` must have been previously allocated or reallocated given `layout`.
+    /// `new_size` must be larger or equal to `layout.size()`.
+    #[cfg(feature = "realloc-shift-down")]
+    #[must_use = "the allocation may have moved, and the old pointer is invalid if so"]
+    pub unsafe fn grow_shift_down(
+        &mut self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<NonNull<u8>, ()> {
+        debug_assert!(new_size >= old_layout.size());
         self.scan_for_errors();
 
-        let (tag_ptr, tag) = tag_from_alloc_ptr(ptr.as_ptr(), layout.size());
+        let (tag_ptr, tag) = tag_from_alloc_ptr(ptr.as_ptr(), old_layout.size());
         let chunk_base = tag.chunk_base();
+        let mut chunk_acme = tag_ptr.add(TAG_SIZE);
 
         debug_assert!(tag.is_allocated());
-        debug_assert!(is_chunk_size(chunk_base, tag_ptr.add(TAG_SIZE)));
+        debug_assert!(is_chunk_size(chunk_base, chunk_acme));
 
-        // the word immediately after the allocation
-        let new_post_alloc_ptr = align_up(ptr.as_ptr().add(new_size));
-        // the tag position, accounting for the minimum size of a chunk
-        let mut new_tag_ptr = chunk_base.add(MIN_TAG_OFFSET).max(new_post_alloc_ptr);
+        if !is_gap_below(chunk_base) {
+            return Err(());
+        }
 
-        // if the remainder between the new required size and the originally allocated
-        // size is large enough, free the remainder, otherwise leave it
-        if is_chunk_size(new_tag_ptr, tag_ptr) {
-            let mut acme = tag_ptr.add(TAG_SIZE);
-            let new_acme = new_tag_ptr.add(TAG_SIZE);
+        let (below_base, below_size) = gap_acme_to_base_size(chunk_base);
+
+        let above_size = if tag.is_above_free() { gap_base_to_size(chunk_acme).read() } else { 0 };
+
+        let alloc_base = align_up_by(below_base, old_layout.align().max(ALIGN) - 1);
+
+        if alloc_base.add(new_size + TAG_SIZE) > chunk_acme.add(above_size) {
+            return Err(());
+        }
+
+        self.deregister_gap(below_base, bin_of_size(below_size));
+
+        if above_size != 0 {
+            self.deregister_gap(chunk_acme, bin_of_size(above_size));
+            chunk_acme = chunk_acme.add(above_size);
+        }
+
+        // the regions may overlap, move the contents before overwriting any metadata
+        alloc_base.copy_from(ptr.as_ptr(), old_layout.size());
+
+        #[cfg(feature = "counters")]
+        {
+            self.counters.account_dealloc(old_layout.size());
+            self.counters.account_relocation();
+        }
+
+        Ok(self.allocate_chunk(below_base, chunk_acme, alloc_base, new_size))
+    }
+
+    /// Shrink a previously allocated/reallocated region of memory to `new_size`.
+    ///
+    /// This function is infallible given valid inputs, and the reallocation will always be
+    /// done in-place, maintaining the validity of the pointer.
+    ///
+    /// # Safety
+    /// - `ptr` must have been previously allocated or reallocated given `layout`.
+    /// - `new_size` must be smaller or equal to `layout.size()`.
+    /// - `new_size` should be nonzero.
+    pub unsafe fn shrink(&mut self, ptr: NonNull<u8>, layout: Layout, new_size: usize) {
+        debug_assert!(new_size != 0);
+        debug_assert!(new_size <= layout.size());
+        self.scan_for_errors();
+
+        let (tag_ptr, tag) = tag_from_alloc_ptr(ptr.as_ptr(), layout.size());
+        let chunk_base = tag.chunk_base();
+
+        debug_assert!(tag.is_allocated());
+        debug_assert!(is_chunk_size(chunk_base, tag_ptr.add(TAG_SIZE)));
+
+        // the word immediately after the allocation
+        let new_post_alloc_ptr = align_up(ptr.as_ptr().add(new_size));
+        // the tag position, accounting for the minimum size of a chunk
+        let mut new_tag_ptr = chunk_base.add(MIN_TAG_OFFSET).max(new_post_alloc_ptr);
+
+        // if the remainder between the new required size and the originally allocated
+        // size is large enough, free the remainder, otherwise leave it
+        if is_chunk_size(new_tag_ptr, tag_ptr) {
+            let mut acme = tag_ptr.add(TAG_SIZE);
+            let new_acme = new_tag_ptr.add(TAG_SIZE);
 
             if tag.is_above_free() {
                 let above_size = gap_base_to_size(acme).read();
@@ -655,6 +1333,159 @@ impl<O: OomHandler> Talc<O> {
         self.counters.account_shrink_in_place(layout.size(), new_size);
     }
 
+    /// Shrink a previously allocated/reallocated region of memory to `new_size`,
+    /// without releasing any of the memory backing it.
+    ///
+    /// Unlike [`shrink`](Talc::shrink), no gap is split off, so a later
+    /// [`grow_in_place`](Talc::grow_in_place) back up to the original size, or
+    /// up to the [`usable_size`](Talc::usable_size), is guaranteed to succeed and is cheap.
+    /// This suits buffers that oscillate in size, avoiding churn in the bins.
+    ///
+    /// The tradeoff is that the retained memory can't be allocated to anything else until
+    /// the allocation is freed or shrunk with [`shrink`](Talc::shrink).
+    ///
+    /// # Safety
+    /// - `ptr` must have been previously allocated or reallocated given `layout`.
+    /// - `new_size` must be smaller or equal to `layout.size()`.
+    /// - `new_size` should be nonzero.
+    pub unsafe fn shrink_keep(&mut self, ptr: NonNull<u8>, layout: Layout, new_size: usize) {
+        debug_assert!(new_size != 0);
+        debug_assert!(new_size <= layout.size());
+        self.scan_for_errors();
+
+        let (tag_ptr, tag) = tag_from_alloc_ptr(ptr.as_ptr(), layout.size());
+
+        debug_assert!(tag.is_allocated());
+
+        // the word immediately after the allocation points to the unmoved tag
+        let new_post_alloc_ptr = align_up(ptr.as_ptr().add(new_size));
+        if new_post_alloc_ptr != tag_ptr {
+            new_post_alloc_ptr.cast::<*mut u8>().write(tag_ptr);
+        }
+
+        #[cfg(feature = "counters")]
+        self.counters.account_shrink_in_place(layout.size(), new_size);
+    }
+
+    /// Reallocate a previously allocated/reallocated region of memory to `new_size`
+    /// with an alignment of `new_align`, which may be stricter than the original alignment.
+    ///
+    /// If `ptr` already satisfies `new_align`, this reallocates in-place where possible.
+    /// Otherwise, the memory is moved to a new allocation and the contents are copied.
+    ///
+    /// Returns `Err` if a new allocation was required but could not be made, in which
+    /// case the original allocation is left untouched.
+    ///
+    /// # Safety
+    /// - `ptr` must have been previously allocated or reallocated given `old_layout`.
+    /// - `new_size` and `new_align` must form a valid [`Layout`].
+    /// - `new_size` must be nonzero.
+    #[must_use = "the allocation may have moved, and the old pointer is invalid if so"]
+    pub unsafe fn realloc_aligned(
+        &mut self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+        new_align: usize,
+    ) -> Result<NonNull<u8>, ()> {
+        debug_assert!(new_size != 0);
+        debug_assert!(Layout::from_size_align(new_size, new_align).is_ok());
+
+        if ptr.as_ptr() as usize & (new_align - 1) == 0 {
+            if new_size > old_layout.size() {
+                if let Ok(ptr) = self.grow_in_place(ptr, old_layout, new_size) {
+                    return Ok(ptr);
+                }
+            } else {
+                if new_size < old_layout.size() {
+                    self.shrink(ptr, old_layout, new_size);
+                }
+
+                return Ok(ptr);
+            }
+        }
+
+        // either the alignment is insufficient or growing in-place failed
+        let allocation = self.malloc(Layout::from_size_align_unchecked(new_size, new_align))?;
+        allocation.as_ptr().copy_from_nonoverlapping(ptr.as_ptr(), old_layout.size().min(new_size));
+        self.free(ptr, old_layout);
+
+        #[cfg(feature = "counters")]
+        self.counters.account_relocation();
+
+        Ok(allocation)
+    }
+
+    /// Move a previously allocated/reallocated region of memory to the free memory nearest
+    /// to `toward`, copying the contents. Returns the new location.
+    ///
+    /// Every gap is searched, and the allocation may be placed anywhere within one. This is
+    /// the primitive for compacting movable allocations, e.g. moving them toward the base
+    /// of a heap to make room above them.
+    ///
+    /// The new location never overlaps the old, so the memory bordering the allocation is not
+    /// considered. Returns `Err` if no gap can hold `layout`, in which case the allocation is
+    /// left untouched. The OOM handler is not invoked.
+    ///
+    /// # Safety
+    /// `ptr` must have been previously allocated or reallocated given `layout`.
+    #[must_use = "the allocation has moved, and the old pointer is invalid"]
+    pub unsafe fn relocate(
+        &mut self,
+        ptr: NonNull<u8>,
+        layout: Layout,
+        toward: *mut u8,
+    ) -> Result<NonNull<u8>, ()> {
+        self.scan_for_errors();
+        self.flush_hot_cache();
+
+        let required_chunk_size = Self::required_chunk_size(layout.size());
+        let align_mask = layout.align().max(ALIGN) - 1;
+        let toward = toward as usize & !align_mask;
+
+        // (gap base, gap bin, allocation base, distance from toward)
+        let mut nearest: Option<(*mut u8, usize, *mut u8, usize)> = None;
+
+        let mut next_bin = self.next_available_bin(0);
+        while let Some(bin) = next_bin {
+            for node_ptr in LlistNode::iter_mut(*self.get_bin_ptr(bin)) {
+                let size = gap_node_to_size(node_ptr).read();
+
+                if size >= required_chunk_size {
+                    let base = gap_node_to_base(node_ptr);
+                    let lowest = align_up_by(base, align_mask) as usize;
+                    // the tag must still fit between the allocation and the gap's acme
+                    let highest = (base as usize + size - TAG_SIZE - layout.size()) & !align_mask;
+
+                    if lowest <= highest {
+                        let alloc_base = toward.clamp(lowest, highest);
+                        let distance = alloc_base.abs_diff(toward);
+
+                        if nearest.map_or(true, |(.., nearest)| distance < nearest) {
+                            let alloc_base = base.add(alloc_base - base as usize);
+                            nearest = Some((base, bin, alloc_base, distance));
+                        }
+                    }
+                }
+            }
+
+            next_bin = self.next_available_bin(bin + 1);
+        }
+
+        let (base, bin, alloc_base, _) = nearest.ok_or(())?;
+        let acme = gap_base_to_acme(base);
+        self.deregister_gap(base, bin);
+
+        let allocation = self.allocate_chunk(base, acme, alloc_base, layout.size());
+        allocation.as_ptr().copy_from_nonoverlapping(ptr.as_ptr(), layout.size());
+        self.free(ptr, layout);
+
+        #[cfg(feature = "counters")]
+        self.counters.account_relocation();
+
+        Ok(allocation)
+    }
+
     /// Returns an uninitialized [`Talc`].
     ///
     /// If you don't want to handle OOM, use [`ErrOnOom`].
@@ -666,17 +1497,102 @@ impl<O: OomHandler> Talc<O> {
             availability_low: 0,
             availability_high: 0,
             bins: null_mut(),
+            min_split_size: MIN_CHUNK_SIZE,
+            claimed_extent: Span::empty(),
+            #[cfg(feature = "counters")]
+            max_claimed_bytes: usize::MAX,
+            #[cfg(feature = "next-fit")]
+            next_fit_cursor: None,
+            #[cfg(feature = "hot-cache")]
+            hot_cache: None,
 
             #[cfg(feature = "counters")]
             counters: counters::Counters::new(),
+
+            #[cfg(feature = "histogram")]
+            size_histogram: [0; BIN_COUNT],
+
+            #[cfg(feature = "scan-metrics")]
+            gap_searches: 0,
+            #[cfg(feature = "scan-metrics")]
+            bins_scanned: 0,
         }
     }
 
+    /// Returns the smallest leftover, in bytes, that allocation will split off as a new gap.
+    pub fn min_split_size(&self) -> usize {
+        self.min_split_size
+    }
+
+    /// Set the smallest leftover, in bytes, that `malloc` and `grow_in_place`
+    /// will split off and register as a new gap.
+    ///
+    /// Smaller leftovers are absorbed into the allocation instead. Raising this
+    /// trades some memory efficiency for fewer tiny gaps clogging the smallest bins.
+    ///
+    /// The value is rounded up to a multiple of `usize` and is at least the
+    /// minimum chunk size (`3 * usize`), which is also the default.
+    pub fn set_min_split_size(&mut self, min_split_size: usize) {
+        let min_split_size = min_split_size.max(MIN_CHUNK_SIZE);
+        self.min_split_size = min_split_size.saturating_add(ALIGN - 1) & !(ALIGN - 1);
+    }
+
+    /// Returns the cap on claimed memory in bytes, see
+    /// [`set_max_claimed_bytes`](Talc::set_max_claimed_bytes).
+    #[cfg(feature = "counters")]
+    pub fn max_claimed_bytes(&self) -> usize {
+        self.max_claimed_bytes
+    }
+
+    /// Cap the memory the OOM handler is asked to provide, in bytes. The default is no cap.
+    ///
+    /// If [`claimed_bytes`](crate::Counters::claimed_bytes) plus the size of a failing allocation
+    /// exceeds the cap, the allocation fails without invoking the OOM handler. This bounds
+    /// growth from runaway allocation sizes, e.g. when the handler acquires memory from the OS.
+    ///
+    /// The check is conservative, as free memory at the edge of a heap may reduce what the
+    /// handler would need to acquire. It also can't constrain how much the handler decides
+    /// to claim, so the handler may overshoot the cap once. Handlers can consult
+    /// [`max_claimed_bytes`](Talc::max_claimed_bytes) to avoid this.
+    #[cfg(feature = "counters")]
+    pub fn set_max_claimed_bytes(&mut self, max_claimed_bytes: usize) {
+        self.max_claimed_bytes = max_claimed_bytes;
+    }
+
+    /// Returns a [`Span`] covering every heap that's been claimed, or `None` if none have been.
+    ///
+    /// This grows with [`claim`](Talc::claim) and [`extend`](Talc::extend), but
+    /// [`truncate`](Talc::truncate) doesn't shrink it, so it may cover released memory.
+    /// It may also cover memory between heaps that was never claimed.
+    pub fn claimed_extent(&self) -> Option<Span> {
+        if self.claimed_extent.is_empty() { None } else { Some(self.claimed_extent) }
+    }
+
+    /// Identifies the allocator by the address of its metadata, which doesn't move
+    /// while any heap exists. Used to check that heaps are resized by their owner.
+    #[cfg(debug_assertions)]
+    pub(crate) fn metadata_addr(&self) -> usize {
+        self.bins as usize
+    }
+
+    /// Expand the [`claimed_extent`](Talc::claimed_extent) to cover `heap`.
+    fn cover_claimed_extent(&mut self, heap: Span) {
+        self.claimed_extent = match self.claimed_extent() {
+            Some(extent) => extent.fit_over(heap),
+            None => heap,
+        };
+    }
+
     /// Returns the minimum [`Span`] containing this heap's allocated memory.
     /// # Safety
     /// `heap` must be the return value of a heap manipulation function.
+    ///
+    /// With debug assertions, a `heap` that's been superseded, such as the heap
+    /// from before an [`extend`](Talc::extend), is likely to be caught with a panic.
+    #[track_caller]
     pub unsafe fn get_allocated_span(&self, heap: Span) -> Span {
         assert!(heap.size() >= MIN_HEAP_SIZE);
+        self.check_heap(heap);
 
         let (mut base, mut acme) = heap.get_base_acme().unwrap();
 
@@ -695,18 +1611,177 @@ impl<O: OomHandler> Talc<O> {
         Span::new(base, acme)
     }
 
+    /// Returns the number of gaps and the total number of free bytes in the `bin`th bin.
+    ///
+    /// Bins are sorted by size, with bin `0` holding the smallest gaps.
+    ///
+    /// This walks the bin's list of gaps, and is thus O(n) in the number of gaps in the bin.
+    ///
+    /// # Panics
+    /// Panics if `bin` is not smaller than [`Talc::BIN_COUNT`].
+    #[track_caller]
+    pub fn bin_stats(&self, bin: usize) -> (usize, usize) {
+        assert!(bin < BIN_COUNT, "bin {} is out of bounds, see Talc::BIN_COUNT", bin);
+
+        if self.bins.is_null() {
+            return (0, 0);
+        }
+
+        let mut count = 0;
+        let mut total_bytes = 0;
+
+        unsafe {
+            for node in LlistNode::iter_mut(*self.get_bin_ptr(bin)) {
+                count += 1;
+                total_bytes += gap_node_to_size(node).read();
+            }
+        }
+
+        (count, total_bytes)
+    }
+
+    /// Returns the number of allocations made so far, counted by the bin that
+    /// the required chunk size for each falls into, as in [`bin_stats`](Talc::bin_stats).
+    ///
+    /// Every successful [`malloc`](Talc::malloc) is counted, including those made by
+    /// [`grow`](Talc::grow) and [`realloc_aligned`](Talc::realloc_aligned) when moving
+    /// an allocation.
+    #[cfg(feature = "histogram")]
+    pub fn size_histogram(&self) -> &[usize; BIN_COUNT] {
+        &self.size_histogram
+    }
+
+    /// Returns the average number of non-empty bins inspected per search for a gap
+    /// to allocate from, or zero if there have been no searches yet.
+    ///
+    /// Every [`malloc`](Talc::malloc) that isn't satisfied otherwise searches the bins,
+    /// once more each time the OOM handler succeeds. Searches that fail are counted too.
+    /// A high average indicates that allocations are often passing over gaps that are
+    /// too small or misaligned, i.e. that the heap is fragmented.
+    #[cfg(feature = "scan-metrics")]
+    pub fn average_bins_scanned(&self) -> f64 {
+        if self.gap_searches == 0 {
+            0.0
+        } else {
+            self.bins_scanned as f64 / self.gap_searches as f64
+        }
+    }
+
+    /// Returns the size of the largest allocation with alignment `align`
+    /// that would currently succeed without invoking the OOM handler.
+    ///
+    /// Returns zero if no memory is available.
+    ///
+    /// If `align` is at most [`ALIGN`](crate::ptr_utils::ALIGN), only the largest nonempty
+    /// bin is walked. Otherwise, all gaps are walked, as any of them might fit best.
+    ///
+    /// # Panics
+    /// Panics if `align` is not a power of two.
+    #[track_caller]
+    pub fn largest_allocatable(&self, align: usize) -> usize {
+        assert!(align.is_power_of_two(), "align {} is not a power of two", align);
+
+        if self.bins.is_null() {
+            return 0;
+        }
+
+        let align_mask = align.max(ALIGN) - 1;
+        let mut largest = 0;
+
+        for bin in (0..BIN_COUNT).rev() {
+            let head = unsafe { *self.get_bin_ptr(bin) };
+
+            if head.is_none() {
+                continue;
+            }
+
+            for node in unsafe { LlistNode::iter_mut(head) } {
+                let base = unsafe { gap_node_to_base(node) };
+                let acme = base.wrapping_add(unsafe { gap_node_to_size(node).read() });
+                let alloc_base = align_up_by(base, align_mask);
+
+                let available = (acme as usize).saturating_sub(alloc_base as usize + TAG_SIZE);
+                largest = largest.max(available);
+            }
+
+            // gaps in lower bins are smaller, and lose as much or more to alignment
+            if align_mask < ALIGN {
+                break;
+            }
+        }
+
+        largest
+    }
+
+    /// Merges any free gaps that directly border each other, returning the number of merges.
+    ///
+    /// Gaps are always coalesced upon being freed, so this should never find anything
+    /// to merge. It's intended as a recovery and maintenance operation for programs that
+    /// manipulate their heaps extensively, after which the allocator's invariants are
+    /// verified (if `debug_assertions` are enabled).
+    ///
+    /// This walks every gap, restarting after each merge, and is thus relatively slow.
+    pub fn coalesce_all(&mut self) -> usize {
+        if self.bins.is_null() {
+            return 0;
+        }
+
+        self.flush_hot_cache();
+
+        let mut merges = 0;
+
+        'restart: loop {
+            let mut bin = 0;
+
+            while let Some(next_bin) = self.next_available_bin(bin) {
+                unsafe {
+                    for node in LlistNode::iter_mut(*self.get_bin_ptr(next_bin)) {
+                        let base = gap_node_to_base(node);
+
+                        // gaps are usually preceded by an allocated chunk's tag, check otherwise
+                        if is_gap_below(base) {
+                            let (low_base, low_size) = gap_acme_to_base_size(base);
+                            let acme = gap_base_to_acme(base);
+
+                            self.deregister_gap(base, next_bin);
+                            self.deregister_gap(low_base, bin_of_size(low_size));
+                            self.register_gap(low_base, acme);
+
+                            merges += 1;
+                            continue 'restart;
+                        }
+                    }
+                }
+
+                bin = next_bin + 1;
+            }
+
+            break;
+        }
+
+        self.scan_for_errors();
+
+        merges
+    }
+
     /// Attempt to initialize a new heap for the allocator.
     ///
     /// Note:
     /// * Each heap reserves a `usize` at the bottom as fixed overhead.
     /// * Metadata will be placed into the bottom of the first successfully established heap.
     /// It is currently ~1KiB on 64-bit systems (less on 32-bit). This is subject to change.
+    /// * Allocations never span across heaps, even if the heaps are adjacent in memory.
+    ///   If `memory` borders an established heap, prefer [`extend`](Talc::extend)-ing that
+    ///   heap instead, so that allocations at its edge can still grow in-place.
     ///
     /// # Return Values
     /// The resulting [`Span`] is the actual heap extent, and may
     /// be slightly smaller than requested. Use this to resize the heap.
     /// Any memory outside the claimed heap is free to use.
     ///
+    /// The heap's bounds are aligned inward to [`ALIGN`](crate::ptr_utils::ALIGN),
+    /// so less than `2 * ALIGN` bytes of `memory` are lost to alignment.
+    ///
     /// Returns [`Err`] where
     /// * allocator metadata is not yet established, and there's insufficient memory to do so.
     /// * allocator metadata is established, but the heap is too small
@@ -720,6 +1795,8 @@ impl<O: OomHandler> Talc<O> {
     ///
     /// # Panics
     /// Panics if `memory` contains the null address.
+    #[track_caller]
+    #[must_use = "the returned heap is required to extend or truncate it"]
     pub unsafe fn claim(&mut self, memory: Span) -> Result<Span, ()> {
         self.scan_for_errors();
 
@@ -751,6 +1828,7 @@ impl<O: OomHandler> Talc<O> {
                     #[cfg(feature = "counters")]
                     self.counters.account_claim(aligned_heap.size());
 
+                    self.cover_claimed_extent(aligned_heap);
                     return Ok(aligned_heap);
                 }
             } else {
@@ -760,8 +1838,6 @@ impl<O: OomHandler> Talc<O> {
 
                     // align the metadata pointer against the base of the heap
                     let metadata_ptr = base.add(TAG_SIZE);
-                    // align the tag pointer against the top of the metadata
-                    let post_metadata_ptr = metadata_ptr.add(BIN_ARRAY_SIZE);
 
                     // initialize the bins to None
                     for i in 0..BIN_COUNT {
@@ -773,24 +1849,14 @@ impl<O: OomHandler> Talc<O> {
 
                     // check whether there's enough room on top to free
                     // add_chunk_to_record only depends on self.bins
-                    let metadata_chunk_acme = post_metadata_ptr.add(TAG_SIZE);
-                    if is_chunk_size(metadata_chunk_acme, acme) {
-                        self.register_gap(metadata_chunk_acme, acme);
-                        Tag::write(post_metadata_ptr.cast(), base, true);
-                    } else {
-                        let tag_ptr = acme.sub(TAG_SIZE).cast::<Tag>();
-
-                        if tag_ptr != post_metadata_ptr.cast() {
-                            post_metadata_ptr.cast::<*mut Tag>().write(tag_ptr);
-                        }
-                        Tag::write(tag_ptr, base, false);
-                    }
+                    self.register_above_metadata(acme);
 
                     self.scan_for_errors();
 
                     #[cfg(feature = "counters")]
                     self.counters.account_claim(aligned_heap.size());
 
+                    self.cover_claimed_extent(aligned_heap);
                     return Ok(aligned_heap);
                 }
             }
@@ -801,9 +1867,108 @@ impl<O: OomHandler> Talc<O> {
         Err(())
     }
 
+    /// Attempt to initialize a new heap for the allocator, from the region of `layout.size()`
+    /// bytes at `ptr`, which is aligned to `layout.align()`. See [`claim`](Talc::claim).
+    ///
+    /// This is convenient for regions from an OS or another allocator, which come with a
+    /// `Layout`. Where `layout.align()` is at least [`ALIGN`](crate::ptr_utils::ALIGN) and
+    /// `layout.size()` is a multiple of it, no bytes are lost to alignment.
+    ///
+    /// # Safety
+    /// The region must satisfy the requirements of `memory` for [`claim`](Talc::claim).
+    ///
+    /// # Panics
+    /// Panics if the region contains the null address.
+    /// With debug assertions, panics if `ptr` isn't aligned to `layout.align()`.
+    #[track_caller]
+    #[must_use = "the returned heap is required to extend or truncate it"]
+    pub unsafe fn claim_layout(&mut self, ptr: NonNull<u8>, layout: Layout) -> Result<Span, ()> {
+        debug_assert!(
+            is_aligned_to(ptr.as_ptr(), layout.align()),
+            "{:p} isn't aligned to {}",
+            ptr,
+            layout.align()
+        );
+
+        self.claim(Span::from_base_size(ptr.as_ptr(), layout.size()))
+    }
+
+    /// Establish the allocator's metadata in `metadata`, ahead of claiming any heaps.
+    ///
+    /// Otherwise, the first heap claimed must be large enough to hold the metadata,
+    /// see [`MIN_FIRST_HEAP_SIZE`](Talc::MIN_FIRST_HEAP_SIZE). This guarantees that an OOM
+    /// handler which lazily claims small regions can't leave the allocator unable to
+    /// establish its metadata. Afterwards, any heap of a few words can be claimed.
+    ///
+    /// `metadata` must be at least [`METADATA_SIZE`](Talc::METADATA_SIZE) bytes
+    /// after being word-aligned inward.
+    ///
+    /// Returns [`Err`] where
+    /// * allocator metadata is already established.
+    /// * `metadata` is too small.
+    ///
+    /// # Safety
+    /// - The memory within `metadata` must be valid for reads and writes, and must not be
+    ///   otherwise accessed while the allocator is in use.
+    /// - `metadata` must not overlap with any heap.
+    pub unsafe fn establish_metadata(&mut self, metadata: Span) -> Result<(), ()> {
+        if !self.bins.is_null() || metadata.word_align_inward().size() < Self::METADATA_SIZE {
+            return Err(());
+        }
+
+        let metadata_ptr = metadata.word_align_inward().get_base_acme().unwrap().0.cast::<Bin>();
+
+        for i in 0..BIN_COUNT {
+            metadata_ptr.add(i).write(None);
+        }
+
+        self.bins = metadata_ptr;
+        Ok(())
+    }
+
+    /// Attempt to initialize a new heap for the allocator, placing the allocator's
+    /// metadata in `metadata` instead of at the bottom of the heap.
+    ///
+    /// This allows the metadata to be kept in a separate (e.g. faster) memory region,
+    /// with all of `memory` being available for allocation. `metadata` must be at least
+    /// [`METADATA_SIZE`](Talc::METADATA_SIZE) bytes after being word-aligned inward.
+    ///
+    /// See [`claim`](Talc::claim) for further details.
+    ///
+    /// Returns [`Err`] where
+    /// * allocator metadata is already established.
+    /// * `metadata` is too small.
+    /// * `memory` is too small (less than around `4 * usize` for now).
+    ///
+    /// # Safety
+    /// - The requirements of [`claim`](Talc::claim) for `memory`.
+    /// - The memory within `metadata` must be valid for reads and writes, and must not be
+    ///   otherwise accessed while the allocator is in use.
+    /// - `metadata` must not overlap with `memory` or any other heap.
+    ///
+    /// # Panics
+    /// Panics if `memory` contains the null address.
+    #[track_caller]
+    #[must_use = "the returned heap is required to extend or truncate it"]
+    pub unsafe fn claim_with_metadata(&mut self, metadata: Span, memory: Span) -> Result<Span, ()> {
+        self.establish_metadata(metadata)?;
+
+        let result = self.claim(memory);
+
+        if result.is_err() {
+            // leave the metadata unestablished, as no heap was established
+            self.bins = null_mut();
+        }
+
+        result
+    }
+
     /// Increase the extent of a heap. The new extent of the heap is returned,
     /// and will be equal to or slightly smaller than requested.
     ///
+    /// The heap can be extended downward, upward, or both. Memory found below
+    /// the heap's base can thus be incorporated without claiming a new heap.
+    ///
     /// # Safety
     /// - `old_heap` must be the return value of a heap-manipulation function
     /// of this allocator instance.
@@ -813,8 +1978,13 @@ impl<O: OomHandler> Talc<O> {
     /// # Panics
     /// This function panics if:
     /// - `old_heap` is too small or heap metadata is not yet allocated
-    /// - `req_heap` doesn't contain `old_heap`
+    /// - `req_heap` doesn't contain `old_heap`, e.g. because computing it overflowed
     /// - `req_heap` contains the null address
+    /// - with debug assertions, `old_heap` isn't the heap's current extent, e.g. it's stale
+    ///
+    /// `req_heap` may reach the top of the address space, its acme is aligned
+    /// down like any other. [`Span::extend_low`] and [`Span::extend_high`] saturate
+    /// instead of wrapping, while [`Span::extend`] panics on overflow.
     ///
     /// A recommended pattern for satisfying these criteria is:
     /// ```rust
@@ -830,13 +2000,18 @@ impl<O: OomHandler> Talc<O> {
     /// // SAFETY: be sure not to extend into memory we can't use
     /// let new_heap = unsafe { talc.extend(old_heap, new_heap) };
     /// ```
+    #[track_caller]
+    #[must_use = "the old heap is stale, the returned heap is required to resize it again"]
     pub unsafe fn extend(&mut self, old_heap: Span, req_heap: Span) -> Span {
         assert!(!self.bins.is_null());
         assert!(old_heap.size() >= MIN_HEAP_SIZE);
+        assert!(!req_heap.is_empty(), "new_heap is empty, did computing it overflow?");
         assert!(req_heap.contains_span(old_heap), "new_heap must contain old_heap");
         assert!(!req_heap.contains(null_mut()), "new_heap covers the null address!");
 
+        self.check_heap(old_heap);
         self.scan_for_errors();
+        self.flush_hot_cache();
 
         let (old_base, old_acme) = old_heap.word_align_inward().get_base_acme().unwrap();
         let (new_base, new_acme) = req_heap.word_align_inward().get_base_acme().unwrap();
@@ -876,6 +2051,7 @@ impl<O: OomHandler> Talc<O> {
         #[cfg(feature = "counters")]
         self.counters.account_extend(old_heap.size(), ret_heap.size());
 
+        self.cover_claimed_extent(ret_heap);
         ret_heap
     }
 
@@ -901,6 +2077,7 @@ impl<O: OomHandler> Talc<O> {
     /// - `old_heap` doesn't contain `req_heap`
     /// - `req_heap` doesn't contain all the allocated memory in `old_heap`
     /// - the heap metadata is not yet allocated, see [`claim`](Talc::claim)
+    /// - with debug assertions, `old_heap` isn't the heap's current extent, e.g. it's stale
     ///
     /// # Usage
     ///
@@ -921,12 +2098,16 @@ impl<O: OomHandler> Talc<O> {
     ///     .fit_over(unsafe { talc.get_allocated_span(old_heap) });
     ///
     /// // truncate the heap
-    /// unsafe { talc.truncate(old_heap, new_heap); }
+    /// let new_heap = unsafe { talc.truncate(old_heap, new_heap) };
     /// ```
+    #[track_caller]
+    #[must_use = "the old heap is stale, the returned heap is required to resize it again"]
     pub unsafe fn truncate(&mut self, old_heap: Span, req_heap: Span) -> Span {
         assert!(!self.bins.is_null(), "no heaps have been successfully established!");
 
+        self.check_heap(old_heap);
         self.scan_for_errors();
+        self.flush_hot_cache();
 
         let new_heap = req_heap.word_align_inward();
 
@@ -998,9 +2179,258 @@ impl<O: OomHandler> Talc<O> {
         ret_heap
     }
 
-    #[cfg(not(debug_assertions))]
-    fn scan_for_errors(&self) {}
-
+    /// Forgets all heaps and the allocator metadata, returning the allocator to its
+    /// freshly [`new`](Talc::new) state, save for the OOM handler and settings.
+    ///
+    /// All memory previously claimed becomes unmanaged, and may be reused or claimed again.
+    ///
+    /// Returns the region the metadata occupied, if it was established.
+    ///
+    /// # Safety
+    /// There must be no live allocations.
+    ///
+    /// # Panics
+    /// With the `"counters"` feature, panics if there are live allocations.
+    #[track_caller]
+    pub unsafe fn reset(&mut self) -> Option<Span> {
+        #[cfg(feature = "counters")]
+        assert!(
+            self.counters.allocation_count == 0,
+            "{} allocations are still live",
+            self.counters.allocation_count
+        );
+
+        self.scan_for_errors();
+
+        if self.bins.is_null() {
+            return None;
+        }
+
+        let metadata = Span::from_base_size(self.bins.cast(), Self::METADATA_SIZE);
+
+        self.bins = null_mut();
+        self.availability_low = 0;
+        self.availability_high = 0;
+        self.claimed_extent = Span::empty();
+
+        #[cfg(feature = "next-fit")]
+        {
+            self.next_fit_cursor = None;
+        }
+        #[cfg(feature = "hot-cache")]
+        {
+            self.hot_cache = None;
+        }
+
+        #[cfg(feature = "counters")]
+        self.counters.account_reset();
+
+        Some(metadata)
+    }
+
+    /// Frees every allocation at once, leaving each heap in `heaps` as a single gap,
+    /// as if it was just claimed. This is much faster than freeing each allocation.
+    ///
+    /// The heaps and the allocator's metadata are kept, along with the OOM handler and
+    /// settings. Use [`reset`](Talc::reset) to forget the heaps too.
+    ///
+    /// # Safety
+    /// This frees every allocation, live or not. Nothing may access them thereafter,
+    /// and they must not be freed or reallocated again, otherwise memory is corrupted.
+    ///
+    /// `heaps` must contain every heap of this allocator, each being the return value of
+    /// the last heap-manipulation function called on it. Heaps that have been truncated
+    /// to an empty span should be left out.
+    ///
+    /// With the `"leak-attribution"` feature, a [`Talck`](crate::Talck)'s records of the
+    /// freed allocations are kept, as `Talc` isn't aware of them.
+    ///
+    /// # Panics
+    /// With debug assertions, panics if any of `heaps` doesn't look like a current heap.
+    #[track_caller]
+    pub unsafe fn free_all(&mut self, heaps: &[Span]) {
+        if self.bins.is_null() {
+            return;
+        }
+
+        for &heap in heaps {
+            self.check_heap(heap);
+        }
+
+        for b in 0..BIN_COUNT {
+            self.get_bin_ptr(b).write(None);
+        }
+
+        self.availability_low = 0;
+        self.availability_high = 0;
+
+        #[cfg(feature = "next-fit")]
+        {
+            self.next_fit_cursor = None;
+        }
+        #[cfg(feature = "hot-cache")]
+        {
+            self.hot_cache = None;
+        }
+
+        #[cfg(feature = "counters")]
+        self.counters.account_free_all();
+
+        for &heap in heaps {
+            let (base, acme) = heap.get_base_acme().unwrap();
+
+            if heap.contains(self.bins.cast()) {
+                // the metadata was placed at the bottom of this heap, keep it allocated
+                let metadata_chunk_base = self.bins.cast::<u8>().sub(TAG_SIZE);
+
+                // anything below it was added by extending the heap downward
+                if metadata_chunk_base != base {
+                    self.register_gap(base.add(TAG_SIZE), self.bins.cast());
+                }
+                Tag::write(base.cast(), null_mut(), metadata_chunk_base != base);
+
+                self.register_above_metadata(acme);
+            } else {
+                Tag::write(base.cast(), null_mut(), true);
+                self.register_gap(base.add(TAG_SIZE), acme);
+            }
+        }
+
+        self.scan_for_errors();
+    }
+
+    /// Registers the memory between the metadata and `acme` as a gap if there's enough
+    /// of it, else has the metadata chunk absorb it, and writes the metadata chunk's tag.
+    ///
+    /// The metadata must be at the bottom of the heap ending at `acme`, as `claim` puts it.
+    unsafe fn register_above_metadata(&mut self, acme: *mut u8) {
+        let metadata_chunk_base = self.bins.cast::<u8>().sub(TAG_SIZE);
+        let post_metadata_ptr = self.bins.cast::<u8>().add(Self::METADATA_SIZE);
+        let metadata_chunk_acme = post_metadata_ptr.add(TAG_SIZE);
+
+        if is_chunk_size(metadata_chunk_acme, acme) {
+            self.register_gap(metadata_chunk_acme, acme);
+            Tag::write(post_metadata_ptr.cast(), metadata_chunk_base, true);
+        } else {
+            let tag_ptr = acme.sub(TAG_SIZE).cast::<Tag>();
+
+            if tag_ptr != post_metadata_ptr.cast() {
+                post_metadata_ptr.cast::<*mut Tag>().write(tag_ptr);
+            }
+            Tag::write(tag_ptr, metadata_chunk_base, false);
+        }
+    }
+
+    // The integrity checks below are expensive, scan_for_errors in particular walks every gap.
+    // They're called unconditionally in hot paths, so without debug assertions (i.e. in release
+    // builds) they must be no-ops, as the empty #[inline(always)] definitions below are.
+    // release_integrity_checks_test, run with --release in CI, ensures that's what's compiled.
+
+    #[cfg(not(debug_assertions))]
+    #[inline(always)]
+    fn check_bordering_gap(&self, _: *mut u8, _: usize) {}
+
+    #[cfg(debug_assertions)]
+    /// Debugging function for checking that a gap about to be coalesced is plausible.
+    ///
+    /// This catches freeing memory that is no longer within a heap, such as
+    /// after the heap was truncated, before the bookkeeping gets corrupted.
+    fn check_bordering_gap(&self, base: *mut u8, size: usize) {
+        assert!(!self.bins.is_null(), "no heaps have been established, yet memory is being freed!");
+        assert!(
+            base as usize % ALIGN == 0 && size % ALIGN == 0 && size >= MIN_CHUNK_SIZE,
+            "implausible gap {:p} of size {} bordering a freed chunk, was the heap truncated?",
+            base,
+            size
+        );
+        assert!(
+            (base as usize).checked_add(size).is_some(),
+            "gap {:p} of size {} bordering a freed chunk wraps the address space",
+            base,
+            size
+        );
+
+        unsafe {
+            let acme = base.add(size);
+            assert!(
+                gap_acme_to_size(acme).read() == size,
+                "gap {:p}..{:p} bordering a freed chunk has mismatched sizes, was the heap truncated?",
+                base,
+                acme
+            );
+
+            let lower_tag = base.sub(TAG_SIZE).cast::<Tag>().read();
+            assert!(lower_tag.is_allocated() && lower_tag.is_above_free());
+        }
+    }
+
+    #[cfg(not(debug_assertions))]
+    #[inline(always)]
+    fn check_heap(&self, _: Span) {}
+
+    #[cfg(debug_assertions)]
+    #[track_caller]
+    /// Debugging function for checking that a heap passed in by the user is plausible.
+    ///
+    /// This catches passing a stale heap, such as the heap from before an
+    /// [`extend`](Talc::extend), before the bookkeeping gets corrupted.
+    fn check_heap(&self, heap: Span) {
+        assert!(
+            heap.size() >= MIN_HEAP_SIZE && heap == heap.word_align_inward(),
+            "{} is not a heap, heaps returned by talc are word-aligned and nonempty",
+            heap
+        );
+        assert!(
+            self.claimed_extent.contains_span(heap),
+            "{} is not a heap, it is outside the claimed extent {}",
+            heap,
+            self.claimed_extent
+        );
+
+        let (base, acme) = heap.get_base_acme().unwrap();
+
+        unsafe {
+            let base_tag = base.cast::<Tag>().read();
+            assert!(
+                base_tag.is_allocated() && base_tag.chunk_base().is_null(),
+                "{} is not a heap, there's no heap base tag at its base, is it stale?",
+                heap
+            );
+
+            if base_tag.is_above_free() {
+                let (gap_acme, size) = gap_base_to_acme_size(base.add(TAG_SIZE));
+                assert!(
+                    gap_acme <= acme && gap_acme_to_size(gap_acme).read() == size,
+                    "{} is not a heap, the gap at its base is implausible, is it stale?",
+                    heap
+                );
+            }
+
+            if is_gap_below(acme) {
+                let (gap_base, size) = gap_acme_to_base_size(acme);
+                assert!(
+                    gap_base > base && gap_base_to_size(gap_base).read() == size,
+                    "{} is not a heap, the gap at its acme is implausible, is it stale?",
+                    heap
+                );
+
+                let lower_tag = gap_base.sub(TAG_SIZE).cast::<Tag>().read();
+                assert!(lower_tag.is_allocated() && lower_tag.is_above_free());
+            } else {
+                let top_tag = acme.sub(TAG_SIZE).cast::<Tag>().read();
+                assert!(
+                    !top_tag.is_above_free(),
+                    "{} is not a heap, there's no tag at its acme, is it stale?",
+                    heap
+                );
+            }
+        }
+    }
+
+    #[cfg(not(debug_assertions))]
+    #[inline(always)]
+    fn scan_for_errors(&self) {}
+
     #[cfg(debug_assertions)]
     /// Debugging function for checking various assumptions.
     fn scan_for_errors(&self) {
@@ -1059,12 +2489,339 @@ impl<O: OomHandler> Talc<O> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn reserve_test() {
+        let mut arena = [0u8; 10000];
+        let mut talc = Talc::new(unsafe { crate::ClaimOnOom::new(Span::from(&mut arena)) });
+
+        let layout = Layout::from_size_align(1000, 64).unwrap();
+        assert!(!unsafe { talc.has_sufficient_chunk(layout) });
+        assert!(talc.reserve(layout));
+        assert!(unsafe { talc.has_sufficient_chunk(layout) });
+        // the arena's been claimed, the OOM handler can't provide any more memory
+        assert!(!talc.reserve(Layout::from_size_align(20000, 8).unwrap()));
+
+        let ptr = unsafe { talc.malloc(layout) }.unwrap();
+        assert!(ptr.as_ptr() as usize % 64 == 0);
+        unsafe { talc.free(ptr, layout) };
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn oom_handler_providing_no_memory_panics() {
+        struct NoMemoryOnOom;
+
+        impl OomHandler for NoMemoryOnOom {
+            fn handle_oom(_: &mut Talc<Self>, _: Layout) -> Result<(), ()> {
+                Ok(())
+            }
+        }
+
+        let mut talc = Talc::new(NoMemoryOnOom);
+        let layout = Layout::new::<[u64; 8]>();
+        let result = std::panic::catch_unwind(move || unsafe { talc.malloc(layout) });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn never_handles_oom_test() {
+        struct PanicOnOom;
+
+        impl OomHandler for PanicOnOom {
+            fn handle_oom(_: &mut Talc<Self>, _: Layout) -> Result<(), ()> {
+                unreachable!("the OOM handler is never invoked")
+            }
+
+            const NEVER_HANDLES_OOM: bool = true;
+        }
+
+        let mut arena = [0u8; 10000];
+        let mut talc = Talc::new(PanicOnOom);
+        unsafe { talc.claim(Span::from(&mut arena)).unwrap() };
+
+        let layout = Layout::new::<[u8; 20000]>();
+        assert!(unsafe { talc.malloc(layout) }.is_err());
+        assert!(!talc.reserve(layout));
+    }
+
+    #[test]
+    fn coalesce_all_test() {
+        let mut arena = [0u8; 10000];
+        let mut talc = Talc::new(crate::ErrOnOom);
+        unsafe { talc.claim(Span::from(&mut arena)).unwrap() };
+
+        assert!(talc.coalesce_all() == 0);
+
+        // split the free memory into two bordering gaps, which free would never do
+        unsafe {
+            let bin = talc.next_available_bin(0).unwrap();
+            let base = gap_node_to_base((*talc.get_bin_ptr(bin)).unwrap());
+            let (acme, size) = gap_base_to_acme_size(base);
+            let mid = align_up(base.add(size / 2));
+
+            talc.deregister_gap(base, bin);
+            talc.register_gap(base, mid);
+            talc.register_gap(mid, acme);
+        }
+
+        assert!(talc.coalesce_all() == 1);
+        assert!(talc.coalesce_all() == 0);
+
+        let layout = Layout::from_size_align(8000, 8).unwrap();
+        let ptr = unsafe { talc.malloc(layout) }.unwrap();
+        unsafe { talc.free(ptr, layout) };
+    }
+
+    #[cfg(feature = "realloc-shift-down")]
+    #[test]
+    fn grow_shift_down_test() {
+        let mut arena = [0u8; 10000];
+        let mut talc = Talc::new(crate::ErrOnOom);
+        unsafe { talc.claim(Span::from(&mut arena)).unwrap() };
+
+        let layout = Layout::from_size_align(200, 8).unwrap();
+
+        unsafe {
+            let below = talc.malloc(layout).unwrap();
+            let middle = talc.malloc(layout).unwrap();
+            let above = talc.malloc(layout).unwrap();
+            talc.free(below, layout);
+
+            for i in 0..layout.size() {
+                middle.as_ptr().add(i).write(i as u8);
+            }
+
+            // there's no room to grow in-place, nor enough below for a large size
+            assert!(talc.grow_in_place(middle, layout, 300).is_err());
+            assert!(talc.grow_shift_down(middle, layout, 1000).is_err());
+
+            let moved = talc.grow_shift_down(middle, layout, 300).unwrap();
+            assert!(moved.as_ptr() < middle.as_ptr());
+
+            for i in 0..layout.size() {
+                assert!(moved.as_ptr().add(i).read() == i as u8);
+            }
+
+            talc.free(moved, Layout::from_size_align(300, 8).unwrap());
+            talc.free(above, layout);
+        }
+    }
+
+    #[test]
+    fn malloc_segmented_test() {
+        let mut arena = [0u8; 10000];
+        let mut talc = Talc::new(crate::ErrOnOom);
+        unsafe { talc.claim(Span::from(&mut arena)).unwrap() };
+
+        // fragment the heap such that no gap is larger than about 500 bytes
+        let layout = Layout::from_size_align(500, 8).unwrap();
+        let mut blockers = std::vec::Vec::new();
+        while let Ok(ptr) = unsafe { talc.malloc(layout) } {
+            blockers.push(ptr);
+        }
+        for ptr in blockers.iter().step_by(2) {
+            unsafe { talc.free(*ptr, layout) };
+        }
+
+        let total_size = 2000;
+        let mut segments = std::vec::Vec::new();
+        let result =
+            unsafe { talc.malloc_segmented(total_size, |ptr, len| segments.push((ptr, len))) };
+        assert!(result.is_ok());
+
+        assert!(segments.len() >= 4);
+        assert_eq!(segments.iter().map(|&(_, len)| len).sum::<usize>(), total_size);
+
+        for (i, &(ptr, len)) in segments.iter().enumerate() {
+            assert_eq!(ptr.as_ptr() as usize % ALIGN, 0);
+            unsafe { ptr.as_ptr().write_bytes(i as u8, len) };
+        }
+        for (i, &(ptr, len)) in segments.iter().enumerate() {
+            let bytes = unsafe { core::slice::from_raw_parts(ptr.as_ptr(), len) };
+            assert!(bytes.iter().all(|&b| b == i as u8));
+        }
+
+        // nothing is allocated if the request can't be satisfied
+        let mut called = false;
+        let result = unsafe { talc.malloc_segmented(10000, |_, _| called = true) };
+        assert!(result.is_err() && !called);
+
+        for (ptr, len) in segments {
+            unsafe { talc.free(ptr, Layout::from_size_align(len, 1).unwrap()) };
+        }
+        for ptr in blockers.into_iter().skip(1).step_by(2) {
+            unsafe { talc.free(ptr, layout) };
+        }
+
+        // the heap fully coalesces afterwards
+        assert!(unsafe { talc.malloc(Layout::from_size_align(8000, 8).unwrap()) }.is_ok());
+    }
+
+    #[test]
+    fn deterministic_offsets_test() {
+        #[repr(align(4096))]
+        struct Arena([u8; 100000]);
+
+        fn run_ops(arena: &mut Arena) -> std::vec::Vec<usize> {
+            let arena_base = arena.0.as_ptr() as usize;
+            let mut talc = Talc::new(crate::ErrOnOom);
+            unsafe { talc.claim(Span::from(&mut arena.0)).unwrap() };
+
+            let mut offsets = std::vec::Vec::new();
+            let mut allocations = std::vec::Vec::<(NonNull<u8>, Layout)>::new();
+            let mut rng = 0x2545f4914f6cdd1d_u64;
+
+            for _ in 0..2000 {
+                rng ^= rng << 13;
+                rng ^= rng >> 7;
+                rng ^= rng << 17;
+
+                let size = rng as usize % 1000 + 1;
+                let layout = Layout::from_size_align(size, 1 << ((rng >> 32) % 9)).unwrap();
+
+                if rng % 3 != 0 || allocations.is_empty() {
+                    if let Ok(ptr) = unsafe { talc.malloc(layout) } {
+                        offsets.push(ptr.as_ptr() as usize - arena_base);
+                        allocations.push((ptr, layout));
+                    }
+                } else {
+                    let (ptr, layout) =
+                        allocations.swap_remove((rng >> 16) as usize % allocations.len());
+                    unsafe { talc.free(ptr, layout) };
+                }
+            }
+
+            offsets
+        }
+
+        let mut arena_a = std::boxed::Box::new(Arena([0; 100000]));
+        let mut arena_b = std::boxed::Box::new(Arena([0; 100000]));
+
+        assert!(run_ops(&mut arena_a) == run_ops(&mut arena_b));
+    }
+
+    #[test]
+    fn try_grow_in_place_test() {
+        let mut arena = [0u8; 10000];
+        let mut talc = Talc::new(crate::ErrOnOom);
+        unsafe { talc.claim(Span::from(&mut arena)).unwrap() };
+
+        let layout = Layout::from_size_align(100, 8).unwrap();
+
+        unsafe {
+            let allocation = talc.malloc(layout).unwrap();
+            let above = talc.malloc(layout).unwrap();
+
+            // the allocation can't be grown past the allocation above
+            let max_size = talc.try_grow_in_place(allocation, layout, 1000).unwrap_err();
+            assert!(max_size >= layout.size() && max_size < 1000);
+            assert!(talc.try_grow_in_place(allocation, layout, max_size + 1).is_err());
+            let grown = talc.try_grow_in_place(allocation, layout, max_size).unwrap();
+            assert!(grown == allocation);
+
+            let max_layout = Layout::from_size_align(max_size, 8).unwrap();
+            talc.free(above, layout);
+
+            // once it's free, the allocation can grow into its memory
+            let new_max_size = talc.try_grow_in_place(allocation, max_layout, 20000).unwrap_err();
+            assert!(new_max_size > max_size + layout.size());
+            talc.try_grow_in_place(allocation, max_layout, new_max_size).unwrap();
+
+            talc.free(allocation, Layout::from_size_align(new_max_size, 8).unwrap());
+        }
+    }
+
+    #[test]
+    fn establish_metadata_test() {
+        let mut metadata = [0u8; 2000];
+        let mut arena = [0u8; 100];
+
+        // too small to hold the metadata, so can't be claimed first
+        let mut talc = Talc::new(unsafe { crate::ClaimOnOom::new(Span::from(&mut arena)) });
+        assert!(unsafe { talc.malloc(Layout::new::<u64>()) }.is_err());
+        assert!(Span::from(&mut arena).size() < Talc::<crate::ErrOnOom>::MIN_FIRST_HEAP_SIZE);
+
+        let mut talc = Talc::new(unsafe { crate::ClaimOnOom::new(Span::from(&mut arena)) });
+        unsafe { talc.establish_metadata(Span::from(&mut metadata)).unwrap() };
+        assert!(unsafe { talc.establish_metadata(Span::from(&mut metadata)) }.is_err());
+
+        let allocation = unsafe { talc.malloc(Layout::new::<u64>()) }.unwrap();
+        assert!(Span::from(&mut arena).contains(allocation.as_ptr()));
+        unsafe { talc.free(allocation, Layout::new::<u64>()) };
+    }
+
+    #[test]
+    fn claim_with_metadata_test() {
+        let mut metadata = [0u8; 2000];
+        let mut arena = [0u8; 500];
+        let mut talc = Talc::new(crate::ErrOnOom);
+
+        unsafe {
+            // too little memory for the metadata
+            let small_metadata = Span::from(&mut metadata[..100]);
+            assert!(talc.claim_with_metadata(small_metadata, Span::from(&mut arena)).is_err());
+
+            // too little memory for a heap, the metadata isn't kept
+            let small_heap = Span::from(&mut arena[..4]);
+            assert!(talc.claim_with_metadata(Span::from(&mut metadata), small_heap).is_err());
+            assert!(talc.bins.is_null());
+
+            let heap = talc.claim_with_metadata(Span::from(&mut metadata), Span::from(&mut arena));
+            let heap = heap.unwrap();
+
+            // the whole heap is available to allocate, the metadata is elsewhere
+            assert!(talc.get_allocated_span(heap).is_empty());
+            assert!(Span::from(&mut metadata).contains(talc.bins.cast()));
+
+            let layout = Layout::from_size_align(400, 8).unwrap();
+            let allocation = talc.malloc(layout).unwrap();
+            assert!(heap.contains(allocation.as_ptr()));
+            talc.free(allocation, layout);
+
+            // the metadata is established, so this must go through claim instead
+            let mut other_metadata = [0u8; 2000];
+            let other_metadata = Span::from(&mut other_metadata);
+            assert!(talc.claim_with_metadata(other_metadata, Span::empty()).is_err());
+        }
+    }
+
     #[test]
     fn alignment_assumptions_hold() {
         // claim assumes this
         assert!(ALIGN == std::mem::align_of::<Bin>() && ALIGN == std::mem::size_of::<Bin>());
     }
 
+    #[test]
+    fn bin_of_size_properties_hold() {
+        // the allocator relies on the binning being monotonic, not skipping
+        // bins, and staying within BIN_COUNT, else bins get corrupted or lost
+        assert!(unsafe { bin_of_size(MIN_CHUNK_SIZE) } == 0);
+
+        let mut prev_bin = 0;
+        let mut size = MIN_CHUNK_SIZE;
+        while size < 1 << 16 {
+            let bin = unsafe { bin_of_size(size) };
+
+            assert!(bin < BIN_COUNT, "size {} maps to out-of-bounds bin {}", size, bin);
+            assert!(bin >= prev_bin, "size {} maps to a smaller bin than its predecessor", size);
+            assert!(bin <= prev_bin + 1, "size {} skips bins after {}", size, prev_bin);
+
+            prev_bin = bin;
+            size += ALIGN;
+        }
+
+        // only sample larger sizes, checking every size is too slow (especially for MIRI)
+        for shift in 16..usize::BITS - 1 {
+            for eighth in 0..8 {
+                let bin = unsafe { bin_of_size((1 << shift) + eighth * (1 << (shift - 3))) };
+                assert!(bin < BIN_COUNT && bin >= prev_bin);
+                prev_bin = bin;
+            }
+        }
+
+        assert!(unsafe { bin_of_size(usize::MAX & !(ALIGN - 1)) } == BIN_COUNT - 1);
+    }
+
     #[test]
     fn alloc_dealloc_test() {
         const ARENA_SIZE: usize = 10000000;
@@ -1119,58 +2876,955 @@ mod tests {
     }
 
     #[test]
-    fn claim_truncate_extend_test() {
-        // not big enough to fit the metadata
-        let mut tiny_heap = [0u8; BIN_COUNT * WORD_SIZE / 2];
-        let tiny_heap_span: Span = Span::from(&mut tiny_heap);
+    fn large_align_edge_cases_test() {
+        const ARENA_SIZE: usize = 0x10000;
 
-        // big enough with plenty of extra
-        let big_heap = Box::leak(vec![0u8; BIN_COUNT * WORD_SIZE + 100000].into_boxed_slice());
-        let big_heap_span = Span::from(big_heap.as_mut());
+        let arena = Box::leak(vec![0u8; ARENA_SIZE].into_boxed_slice()) as *mut [_];
 
-        let mut talc = Talc::new(crate::ErrOnOom);
+        // offset the heap so it doesn't start on a large alignment boundary
+        let heap = Span::from(unsafe { arena.as_mut().unwrap() }).truncate(7, 0);
+
+        let layouts = [
+            Layout::from_size_align(1, 4096).unwrap(),
+            Layout::from_size_align(4097, 4096).unwrap(),
+            Layout::from_size_align(4096, 4096).unwrap(),
+            Layout::from_size_align(64, 64).unwrap(),
+        ];
+
+        for layout in layouts {
+            let mut talc = Talc::new(crate::ErrOnOom);
+            unsafe { talc.claim(heap).unwrap() };
+
+            let mut allocations = std::vec::Vec::new();
+            while let Ok(ptr) = unsafe { talc.malloc(layout) } {
+                assert_eq!(ptr.as_ptr() as usize % layout.align(), 0, "{:?}", layout);
+                assert!(heap.contains(ptr.as_ptr()));
+                assert!(heap.contains(ptr.as_ptr().wrapping_add(layout.size() - 1)));
+
+                let fill = allocations.len() as u8;
+                unsafe { ptr.as_ptr().write_bytes(fill, layout.size()) };
+                allocations.push(ptr);
+            }
+
+            // a 64KiB heap fits several of each
+            assert!(allocations.len() >= 4, "{:?}", layout);
+
+            for (i, &ptr) in allocations.iter().enumerate() {
+                let bytes = unsafe { core::slice::from_raw_parts(ptr.as_ptr(), layout.size()) };
+                assert!(bytes.iter().all(|&b| b == i as u8), "{:?}", layout);
+            }
+
+            for ptr in allocations {
+                unsafe { talc.free(ptr, layout) };
+            }
+
+            // everything was released and coalesced
+            let whole = Layout::from_size_align(ARENA_SIZE / 2, 8).unwrap();
+            assert!(unsafe { talc.malloc(whole) }.is_ok());
+        }
 
         unsafe {
-            talc.claim(tiny_heap_span).unwrap_err();
+            drop(Box::from_raw(arena));
         }
+    }
 
-        assert!(talc.bins.is_null());
-        assert!(talc.availability_low == 0 && talc.availability_high == 0);
+    #[test]
+    fn zero_size_test() {
+        let mut talc = Talc::new(crate::ErrOnOom);
 
-        let alloc_big_heap = unsafe { talc.claim(big_heap_span).unwrap() };
+        // no heap is required, nothing is allocated
+        let layout = Layout::from_size_align(0, 4096).unwrap();
+        let ptr = talc.malloc_or_dangling(layout).unwrap();
+        assert_eq!(ptr.as_ptr() as usize % 4096, 0);
+        unsafe { talc.free_or_dangling(ptr, layout) };
 
-        assert!(!talc.bins.is_null());
+        let mut arena = [0u8; 10000];
+        unsafe { talc.claim(Span::from(&mut arena)).unwrap() };
 
-        let alloc_big_heap = unsafe {
-            talc.truncate(
-                alloc_big_heap,
-                alloc_big_heap.truncate(500, 500).fit_over(talc.get_allocated_span(alloc_big_heap)),
-            )
-        };
+        let layout = Layout::new::<[u64; 4]>();
+        let ptr = talc.malloc_or_dangling(layout).unwrap();
+        assert!(Span::from(&mut arena).contains(ptr.as_ptr()));
+        unsafe { talc.free_or_dangling(ptr, layout) };
+    }
 
-        let _alloc_tiny_heap = unsafe { talc.claim(tiny_heap_span).unwrap() };
+    #[test]
+    fn minimum_alignment_test() {
+        let mut arena = [0u8; 10000];
+        let mut talc = Talc::new(crate::ErrOnOom);
+        // misalign the heap
+        unsafe { talc.claim(Span::from(&mut arena).truncate(3, 0)).unwrap() };
+
+        let mut allocations = std::vec::Vec::new();
+        for size in 1..=64 {
+            for align in [1, 2, 4] {
+                let layout = Layout::from_size_align(size, align).unwrap();
+                let ptr = unsafe { talc.malloc(layout) }.unwrap();
+                assert_eq!(ptr.as_ptr() as usize % ALIGN, 0, "{:?}", layout);
+                allocations.push((ptr, layout));
+            }
+        }
 
-        let allocation = unsafe {
-            let allocation = talc.malloc(Layout::new::<u128>()).unwrap();
-            allocation.as_ptr().write_bytes(0, Layout::new::<u128>().size());
-            allocation
-        };
+        for (ptr, layout) in allocations {
+            unsafe { talc.free(ptr, layout) };
+        }
+    }
 
-        let alloc_big_heap = unsafe {
-            talc.truncate(
-                alloc_big_heap,
-                alloc_big_heap
-                    .truncate(100000, 100000)
-                    .fit_over(talc.get_allocated_span(alloc_big_heap)),
+    #[test]
+    fn usable_size_test() {
+        let mut arena = [0u8; 10000];
+        let mut talc = Talc::new(crate::ErrOnOom);
+        unsafe { talc.claim(Span::from(&mut arena)).unwrap() };
+
+        for size in [1, 3, ALIGN, ALIGN + 1, 100] {
+            let layout = Layout::from_size_align(size, 1).unwrap();
+            let ptr = unsafe { talc.malloc(layout) }.unwrap();
+
+            let usable = unsafe { talc.usable_size(ptr, layout) };
+            assert!(usable >= size);
+            assert_eq!(usable % ALIGN, 0);
+
+            // growing into the usable size must not move the allocation
+            assert_eq!(unsafe { talc.grow_in_place(ptr, layout, usable) }, Ok(ptr));
+            let new_layout = Layout::from_size_align(usable, 1).unwrap();
+            assert_eq!(unsafe { talc.usable_size(ptr, new_layout) }, usable);
+
+            unsafe { talc.free(ptr, new_layout) };
+        }
+    }
+
+    #[test]
+    fn shrink_keep_test() {
+        let mut arena = [0u8; 10000];
+        let mut talc = Talc::new(crate::ErrOnOom);
+        unsafe { talc.claim(Span::from(&mut arena)).unwrap() };
+        let initial_largest = talc.largest_allocatable(ALIGN);
+
+        let layout = Layout::from_size_align(1000, ALIGN).unwrap();
+        let small_layout = Layout::from_size_align(10, ALIGN).unwrap();
+
+        let ptr = unsafe { talc.malloc(layout).unwrap() };
+        let usable = unsafe { talc.usable_size(ptr, layout) };
+
+        // the whole chunk is retained
+        unsafe { talc.shrink_keep(ptr, layout, small_layout.size()) };
+        assert_eq!(unsafe { talc.usable_size(ptr, small_layout) }, usable);
+
+        // even with the rest of the heap allocated, growing back succeeds in place
+        let filler = Layout::from_size_align(talc.largest_allocatable(ALIGN), ALIGN).unwrap();
+        let filler_ptr = unsafe { talc.malloc(filler).unwrap() };
+        assert_eq!(unsafe { talc.grow_in_place(ptr, small_layout, usable) }, Ok(ptr));
+
+        // shrinking as usual releases the tail instead
+        unsafe { talc.shrink(ptr, Layout::from_size_align(usable, ALIGN).unwrap(), 10) };
+        assert!(talc.largest_allocatable(ALIGN) >= layout.size() / 2);
+
+        unsafe {
+            talc.free(ptr, small_layout);
+            talc.free(filler_ptr, filler);
+        }
+
+        assert_eq!(talc.largest_allocatable(ALIGN), initial_largest);
+    }
+
+    #[test]
+    fn min_split_size_test() {
+        let mut heap = [0u8; BIN_COUNT * WORD_SIZE + 10000];
+
+        let mut talc = Talc::new(crate::ErrOnOom);
+        assert!(talc.min_split_size() == MIN_CHUNK_SIZE);
+
+        talc.set_min_split_size(1);
+        assert!(talc.min_split_size() == MIN_CHUNK_SIZE);
+        talc.set_min_split_size(MIN_CHUNK_SIZE + 1);
+        assert!(talc.min_split_size() == MIN_CHUNK_SIZE + ALIGN);
+
+        unsafe {
+            talc.claim(Span::from(&mut heap)).unwrap();
+        }
+
+        // the leftover is always smaller than this, so the whole gap is absorbed
+        talc.set_min_split_size(heap.len());
+
+        let layout = Layout::new::<u64>();
+        let allocation = unsafe { talc.malloc(layout).unwrap() };
+        assert!(unsafe { talc.malloc(layout) }.is_err());
+
+        unsafe {
+            talc.free(allocation, layout);
+        }
+
+        talc.set_min_split_size(0);
+
+        let allocation = unsafe { talc.malloc(layout).unwrap() };
+        let allocation2 = unsafe { talc.malloc(layout).unwrap() };
+
+        unsafe {
+            talc.free(allocation, layout);
+            talc.free(allocation2, layout);
+        }
+    }
+
+    #[cfg(not(debug_assertions))]
+    #[test]
+    fn release_integrity_checks_test() {
+        // the checks would reject this corrupt state, and dereference the bogus gap,
+        // so getting through them unscathed shows that they don't run
+        let mut talc = Talc::new(crate::ErrOnOom);
+        talc.availability_low = 1;
+
+        talc.scan_for_errors();
+        talc.check_bordering_gap(ALIGN as *mut u8, 1);
+    }
+
+    #[test]
+    fn realloc_aligned_test() {
+        let mut heap = [0u8; BIN_COUNT * WORD_SIZE + 100000];
+
+        let mut talc = Talc::new(crate::ErrOnOom);
+        unsafe {
+            talc.claim(Span::from(&mut heap)).unwrap();
+        }
+
+        let layout = Layout::from_size_align(100, 1).unwrap();
+        let mut allocation = unsafe { talc.malloc(layout).unwrap() };
+        unsafe {
+            allocation.as_ptr().write_bytes(0xab, layout.size());
+        }
+
+        // ensure the allocation is not already sufficiently aligned
+        if allocation.as_ptr() as usize % 4096 == 0 {
+            let misaligned = unsafe { talc.malloc(layout).unwrap() };
+            unsafe {
+                misaligned.as_ptr().copy_from_nonoverlapping(allocation.as_ptr(), layout.size());
+                talc.free(allocation, layout);
+            }
+            allocation = misaligned;
+        }
+
+        // increase the alignment while growing
+        let new_layout = Layout::from_size_align(200, 4096).unwrap();
+        let allocation = unsafe {
+            talc.realloc_aligned(allocation, layout, new_layout.size(), new_layout.align()).unwrap()
+        };
+
+        assert!(allocation.as_ptr() as usize % 4096 == 0);
+        for i in 0..layout.size() {
+            assert!(unsafe { allocation.as_ptr().add(i).read() } == 0xab);
+        }
+
+        // the alignment is already sufficient, so this happens in-place
+        let shrunk_layout = Layout::from_size_align(50, 64).unwrap();
+        let shrunk = unsafe {
+            talc.realloc_aligned(
+                allocation,
+                new_layout,
+                shrunk_layout.size(),
+                shrunk_layout.align(),
             )
+            .unwrap()
+        };
+        assert!(shrunk == allocation);
+
+        unsafe {
+            talc.free(shrunk, shrunk_layout);
+        }
+    }
+
+    #[test]
+    fn relocate_test() {
+        let mut arena = [0u8; 10000];
+        let mut talc = Talc::new(crate::ErrOnOom);
+        unsafe { talc.claim(Span::from(&mut arena)).unwrap() };
+
+        let layout = Layout::from_size_align(256, 8).unwrap();
+        let mut allocations = std::vec::Vec::new();
+        while let Ok(ptr) = unsafe { talc.malloc(layout) } {
+            allocations.push(ptr);
+        }
+
+        // no gap can hold another allocation
+        let last = *allocations.last().unwrap();
+        assert!(unsafe { talc.relocate(last, layout, null_mut()) }.is_err());
+
+        // open up gaps low and high in the heap, and move the last allocation low
+        let low = allocations.remove(1);
+        let high = allocations.remove(allocations.len() - 3);
+        unsafe { talc.free(low, layout) };
+        unsafe { talc.free(high, layout) };
+
+        unsafe { last.as_ptr().write_bytes(0xab, layout.size()) };
+        let moved = unsafe { talc.relocate(last, layout, low.as_ptr().wrapping_add(3)) }.unwrap();
+        assert_eq!(moved, low);
+        let bytes = unsafe { core::slice::from_raw_parts(moved.as_ptr(), layout.size()) };
+        assert!(bytes.iter().all(|&b| b == 0xab));
+
+        // an aligned placement is found within a larger gap, near the target
+        let aligned_layout = Layout::from_size_align(128, 128).unwrap();
+        let aligned = unsafe { talc.malloc(aligned_layout) }.unwrap();
+        unsafe { talc.free(allocations[2], layout) };
+        unsafe { talc.free(allocations[3], layout) };
+
+        let target = allocations[3].as_ptr();
+        let aligned = unsafe { talc.relocate(aligned, aligned_layout, target) }.unwrap();
+        assert_eq!(aligned.as_ptr() as usize % 128, 0);
+        assert!((aligned.as_ptr() as usize).abs_diff(target as usize) < 128);
+    }
+
+    #[test]
+    fn bin_stats_test() {
+        let mut heap = [0u8; BIN_COUNT * WORD_SIZE + 10000];
+
+        let mut talc = Talc::new(crate::ErrOnOom);
+        assert!((0..Talc::<crate::ErrOnOom>::BIN_COUNT).all(|b| talc.bin_stats(b) == (0, 0)));
+
+        unsafe {
+            talc.claim(Span::from(&mut heap)).unwrap();
+        }
+
+        let total = |talc: &Talc<_>| {
+            (0..BIN_COUNT).map(|b| talc.bin_stats(b)).fold((0, 0), |a, s| (a.0 + s.0, a.1 + s.1))
         };
 
+        let (count, bytes) = total(&talc);
+        assert!(count == 1);
+        assert!(bytes > 9000 && bytes < 10000);
+
+        // carve out a small gap between allocations
+        let layout = Layout::from_size_align(WORD_SIZE * 5, WORD_SIZE).unwrap();
+        let a = unsafe { talc.malloc(layout).unwrap() };
+        let b = unsafe { talc.malloc(layout).unwrap() };
+        let c = unsafe { talc.malloc(layout).unwrap() };
         unsafe {
+            talc.free(b, layout);
+        }
+
+        let (count, _) = total(&talc);
+        assert!(count == 2);
+
+        let chunk_size = Talc::<crate::ErrOnOom>::required_chunk_size(layout.size());
+        let small_bin = unsafe { bin_of_size(chunk_size) };
+        assert!(talc.bin_stats(small_bin).0 == 1);
+
+        unsafe {
+            talc.free(a, layout);
+            talc.free(c, layout);
+        }
+
+        assert!(total(&talc) == (1, bytes));
+    }
+
+    #[test]
+    fn try_malloc_test() {
+        let mut heap = [0u8; BIN_COUNT * WORD_SIZE + 10000];
+
+        let mut talc = Talc::new(crate::ErrOnOom);
+        assert!(talc.largest_allocatable(1) == 0);
+
+        unsafe {
+            talc.claim(Span::from(&mut heap)).unwrap();
+        }
+
+        // fragment the heap a bit
+        let small = Layout::from_size_align(1000, WORD_SIZE).unwrap();
+        let allocs = [(); 4].map(|_| unsafe { talc.malloc(small).unwrap() });
+        unsafe { talc.free(allocs[1], small) };
+
+        for align in [1, WORD_SIZE, 256, 4096] {
+            let too_large = Layout::from_size_align(20000, align).unwrap();
+            let largest = unsafe { talc.try_malloc(too_large) }.unwrap_err();
+            assert!(largest == talc.largest_allocatable(align));
+
+            // the largest size is exact
+            let layout = Layout::from_size_align(largest, align).unwrap();
+            let ptr = unsafe { talc.try_malloc(layout) }.unwrap();
+            unsafe { talc.free(ptr, layout) };
+
+            let layout = Layout::from_size_align(largest + 1, align).unwrap();
+            assert!(unsafe { talc.try_malloc(layout) } == Err(largest));
+        }
+
+        for ptr in [allocs[0], allocs[2], allocs[3]] {
+            unsafe { talc.free(ptr, small) };
+        }
+    }
+
+    #[cfg(feature = "scan-metrics")]
+    #[test]
+    fn average_bins_scanned_test() {
+        let mut heap = [0u8; 10000];
+
+        let mut talc = Talc::new(crate::ErrOnOom);
+        unsafe {
+            talc.claim(Span::from(&mut heap)).unwrap();
+        }
+
+        assert_eq!(talc.average_bins_scanned(), 0.0);
+
+        let layout = Layout::new::<[usize; 4]>();
+
+        unsafe {
+            // the only gap is found in the first bin inspected
+            let a = talc.malloc(layout).unwrap();
+            let b = talc.malloc(layout).unwrap();
+            assert_eq!(talc.average_bins_scanned(), 1.0);
+
+            // no bin is large enough, so none is inspected
+            assert!(talc.malloc(Layout::from_size_align(100000, 1).unwrap()).is_err());
+            assert_eq!(talc.average_bins_scanned(), 2.0 / 3.0);
+
+            talc.free(a, layout);
+            talc.free(b, layout);
+        }
+    }
+
+    #[cfg(feature = "histogram")]
+    #[test]
+    fn size_histogram_test() {
+        let mut heap = [0u8; BIN_COUNT * WORD_SIZE + 10000];
+
+        let mut talc = Talc::new(crate::ErrOnOom);
+        unsafe {
+            talc.claim(Span::from(&mut heap)).unwrap();
+        }
+
+        assert!(talc.size_histogram().iter().all(|&count| count == 0));
+
+        let small = Layout::new::<u8>();
+        let large = Layout::from_size_align(2000, 1).unwrap();
+        let small_bin = unsafe { bin_of_size(Talc::<crate::ErrOnOom>::required_chunk_size(1)) };
+        let large_bin = unsafe { bin_of_size(Talc::<crate::ErrOnOom>::required_chunk_size(2000)) };
+
+        unsafe {
+            let a = talc.malloc(small).unwrap();
+            let b = talc.malloc(small).unwrap();
+            let c = talc.malloc(large).unwrap();
+
+            // failed allocations aren't counted
+            assert!(talc.malloc(Layout::from_size_align(100000, 1).unwrap()).is_err());
+
+            talc.free(a, small);
+            talc.free(b, small);
+            talc.free(c, large);
+        }
+
+        let histogram = talc.size_histogram();
+        assert!(histogram[small_bin] == 2);
+        assert!(histogram[large_bin] == 1);
+        assert!(histogram.iter().sum::<usize>() == 3);
+    }
+
+    #[test]
+    fn min_chunks_coalesce_test() {
+        let mut heap = [0u8; BIN_COUNT * WORD_SIZE + 10000];
+
+        let mut talc = Talc::new(crate::ErrOnOom);
+        unsafe {
+            talc.claim(Span::from(&mut heap)).unwrap();
+        }
+
+        let total = |talc: &Talc<_>| {
+            (0..BIN_COUNT).map(|b| talc.bin_stats(b)).fold((0, 0), |a, s| (a.0 + s.0, a.1 + s.1))
+        };
+
+        let (count, bytes) = total(&talc);
+        assert!(count == 1);
+
+        let layout = Layout::new::<u8>();
+        let mut allocations = std::vec::Vec::new();
+
+        let orders: [fn(usize, usize) -> usize; 4] = [
+            |i, _| i,
+            |i, n| n - 1 - i,
+            // evens, then odds, leaving gaps between allocations to be bridged
+            |i, n| if i < (n + 1) / 2 { i * 2 } else { (i - (n + 1) / 2) * 2 + 1 },
+            // alternate between the lowest and highest remaining
+            |i, n| if i % 2 == 0 { i / 2 } else { n - 1 - i / 2 },
+        ];
+
+        for order in orders {
+            // fill the whole heap with minimum size chunks
+            while let Ok(ptr) = unsafe { talc.malloc(layout) } {
+                allocations.push(ptr);
+            }
+            assert!(total(&talc).0 == 0);
+
+            let n = allocations.len();
+            for i in 0..n {
+                unsafe { talc.free(allocations[order(i, n)], layout) };
+            }
+            allocations.clear();
+
+            assert!(total(&talc) == (1, bytes), "freed chunks didn't fully coalesce");
+        }
+    }
+
+    #[cfg(feature = "best-fit")]
+    #[test]
+    fn best_fit_test() {
+        let mut heap = [0u8; BIN_COUNT * WORD_SIZE + 10000];
+
+        let mut talc = Talc::new(crate::ErrOnOom);
+        unsafe {
+            talc.claim(Span::from(&mut heap)).unwrap();
+        }
+
+        // these chunks end up in the same bin, see bin_of_size
+        let large = Layout::from_size_align(592, WORD_SIZE).unwrap();
+        let small = Layout::from_size_align(512, WORD_SIZE).unwrap();
+        let sep = Layout::new::<usize>();
+
+        unsafe {
+            let a = talc.malloc(large).unwrap();
+            let s1 = talc.malloc(sep).unwrap();
+            let b = talc.malloc(small).unwrap();
+            let s2 = talc.malloc(sep).unwrap();
+
+            // free the smaller gap first, so that it's not first in the bin
+            talc.free(b, small);
+            talc.free(a, large);
+
+            let c_layout = Layout::from_size_align(504, WORD_SIZE).unwrap();
+            let c = talc.malloc(c_layout).unwrap();
+            assert!(c == b, "the smallest sufficient gap wasn't chosen");
+
+            talc.free(c, c_layout);
+            talc.free(s1, sep);
+            talc.free(s2, sep);
+        }
+    }
+
+    #[cfg(all(feature = "next-fit", not(feature = "best-fit")))]
+    #[test]
+    fn next_fit_test() {
+        let mut heap = [0u8; BIN_COUNT * WORD_SIZE + 10000];
+
+        let mut talc = Talc::new(crate::ErrOnOom);
+        unsafe {
+            talc.claim(Span::from(&mut heap)).unwrap();
+        }
+
+        let layout = Layout::from_size_align(256, WORD_SIZE).unwrap();
+        let sep = Layout::new::<usize>();
+
+        unsafe {
+            // separate the allocations so that their gaps don't coalesce
+            let mut allocs = [NonNull::dangling(); 3];
+            let mut seps = [NonNull::dangling(); 3];
+            for (alloc, sep_alloc) in allocs.iter_mut().zip(seps.iter_mut()) {
+                *alloc = talc.malloc(layout).unwrap();
+                *sep_alloc = talc.malloc(sep).unwrap();
+            }
+
+            // gaps are pushed to the front of their bin, giving [2, 1]
+            talc.free(allocs[1], layout);
+            talc.free(allocs[2], layout);
+
+            let a = talc.malloc(layout).unwrap();
+            assert!(a == allocs[2]);
+
+            // gives [0, 1], but the search resumes from 1
+            talc.free(allocs[0], layout);
+
+            let b = talc.malloc(layout).unwrap();
+            assert!(b == allocs[1], "the search didn't resume from the last position");
+
+            let c = talc.malloc(layout).unwrap();
+            assert!(c == allocs[0], "the search didn't wrap around to the front of the bin");
+
+            for ptr in [a, b, c] {
+                talc.free(ptr, layout);
+            }
+            for ptr in seps {
+                talc.free(ptr, sep);
+            }
+        }
+    }
+
+    #[test]
+    fn claimed_extent_test() {
+        let mut arena = [0u8; 30000];
+        let arena_span = Span::from(&mut arena);
+        let (low, high) = (arena_span.truncate(0, 20000), arena_span.truncate(20000, 0));
+
+        let mut talc = Talc::new(crate::ErrOnOom);
+        assert!(talc.claimed_extent().is_none());
+
+        let low_heap = unsafe { talc.claim(low.truncate(0, 5000)).unwrap() };
+        assert_eq!(talc.claimed_extent(), Some(low_heap));
+
+        let high_heap = unsafe { talc.claim(high).unwrap() };
+        assert_eq!(talc.claimed_extent(), Some(low_heap.fit_over(high_heap)));
+
+        let low_heap = unsafe { talc.extend(low_heap, low) };
+        assert_eq!(talc.claimed_extent(), Some(low_heap.fit_over(high_heap)));
+
+        // truncation doesn't shrink the extent
+        let _ = unsafe { talc.truncate(high_heap, high_heap.truncate(0, 5000)) };
+        assert_eq!(talc.claimed_extent(), Some(low_heap.fit_over(high_heap)));
+    }
+
+    #[test]
+    fn claim_misaligned_test() {
+        let mut arena = [0u8; 20000];
+        let arena_span = Span::from(&mut arena);
+        let mut talc = Talc::new(crate::ErrOnOom);
+
+        // the first claim places the metadata, later ones don't, so check both
+        let regions = [arena_span.truncate(0, 10000), arena_span.truncate(10000, 0)];
+        for (i, region) in regions.into_iter().enumerate() {
+            // put the base at an odd address, and misalign the acme too
+            let skew = 1 + (region.get_base_acme().unwrap().0 as usize & 1);
+            let memory = region.truncate(skew, 3);
+            assert!(memory.get_base_acme().unwrap().0 as usize % 2 == 1);
+
+            let heap = unsafe { talc.claim(memory).unwrap() };
+            let (base, acme) = heap.get_base_acme().unwrap();
+
+            assert!(memory.contains_span(heap), "{}", i);
+            assert!(base as usize % ALIGN == 0 && acme as usize % ALIGN == 0, "{}", i);
+            assert!(memory.size() - heap.size() < 2 * ALIGN, "{}", i);
+        }
+    }
+
+    #[test]
+    fn reset_test() {
+        let mut arena = [0u8; 10000];
+        let arena_span = Span::from(&mut arena);
+
+        let mut talc = Talc::new(crate::ErrOnOom);
+        assert!(unsafe { talc.reset() }.is_none());
+
+        for _ in 0..2 {
+            let heap = unsafe { talc.claim(arena_span).unwrap() };
+
+            let layout = Layout::new::<[u64; 4]>();
+            let ptr = unsafe { talc.malloc(layout).unwrap() };
+            unsafe { talc.free(ptr, layout) };
+
+            let metadata = unsafe { talc.reset() }.unwrap();
+            assert!(heap.contains_span(metadata));
+            assert!(metadata.size() == Talc::<crate::ErrOnOom>::METADATA_SIZE);
+
+            // the allocator has forgotten the arena, so it can be claimed again
+            assert!(talc.claimed_extent().is_none());
+            assert!(unsafe { talc.malloc(layout) }.is_err());
+        }
+    }
+
+    #[cfg(feature = "hot-cache")]
+    #[test]
+    fn hot_cache_test() {
+        let mut arena = [0u8; 10000];
+        let mut talc = Talc::new(crate::ErrOnOom);
+        unsafe { talc.claim(Span::from(&mut arena)).unwrap() };
+        let initial_largest = talc.largest_allocatable(ALIGN);
+
+        let layout = Layout::from_size_align(ALIGN * 12 + 1, 1).unwrap();
+        let ptr = unsafe { talc.malloc(layout).unwrap() };
+
+        // the freed allocation is kept for the next malloc of a similar layout
+        unsafe { talc.free(ptr, layout) };
+        assert!(talc.largest_allocatable(ALIGN) < initial_largest);
+        let similar = Layout::from_size_align(ALIGN * 13, ALIGN).unwrap();
+        assert_eq!(unsafe { talc.malloc(similar) }, Ok(ptr));
+
+        // anything else frees it first
+        unsafe { talc.free(ptr, similar) };
+        let larger = Layout::from_size_align(ALIGN * 14, 1).unwrap();
+        let other = unsafe { talc.malloc(larger).unwrap() };
+        unsafe { talc.free(other, larger) };
+
+        talc.flush_hot_cache();
+        assert_eq!(talc.largest_allocatable(ALIGN), initial_largest);
+    }
+
+    #[cfg(feature = "hot-cache")]
+    #[test]
+    fn hot_cache_segmented_test() {
+        let mut arena = [0u8; 10000];
+        let mut talc = Talc::new(crate::ErrOnOom);
+        unsafe { talc.claim(Span::from(&mut arena)).unwrap() };
+
+        // the cached allocation holds all the free memory
+        let largest = talc.largest_allocatable(ALIGN);
+        let layout = Layout::from_size_align(largest, 1).unwrap();
+        let ptr = unsafe { talc.malloc(layout).unwrap() };
+        unsafe { talc.free(ptr, layout) };
+        assert_eq!(talc.largest_allocatable(ALIGN), 0);
+
+        let mut segments = std::vec::Vec::new();
+        unsafe { talc.malloc_segmented(largest, |ptr, len| segments.push((ptr, len))).unwrap() };
+        assert_eq!(segments, [(ptr, largest)]);
+
+        unsafe { talc.free(ptr, layout) };
+    }
+
+    #[test]
+    fn free_all_test() {
+        let mut arena = [0u8; 10000];
+        let arena_span = Span::from(&mut arena);
+
+        let mut talc = Talc::new(crate::ErrOnOom);
+        let meta_heap = unsafe { talc.claim(arena_span.truncate(2000, 5000)).unwrap() };
+        // leave a gap below the metadata
+        let meta_heap = unsafe { talc.extend(meta_heap, meta_heap.extend_low(1000)) };
+        let heap = unsafe { talc.claim(arena_span.truncate(6000, 1000)).unwrap() };
+
+        let initial_largest = talc.largest_allocatable(ALIGN);
+        #[cfg(feature = "counters")]
+        let initial_counters = *talc.get_counters();
+
+        let layout = Layout::new::<[u8; 100]>();
+        let mut ptrs = std::vec::Vec::new();
+        while let Ok(ptr) = unsafe { talc.malloc(layout) } {
+            ptrs.push(ptr);
+        }
+        // fragment the heaps a bit
+        for ptr in ptrs.iter().step_by(3) {
+            unsafe { talc.free(*ptr, layout) };
+        }
+
+        unsafe { talc.free_all(&[meta_heap, heap]) };
+
+        assert_eq!(talc.largest_allocatable(ALIGN), initial_largest);
+        #[cfg(feature = "counters")]
+        {
+            let counters = talc.get_counters();
+            assert_eq!(counters.allocation_count, 0);
+            assert_eq!(counters.allocated_bytes, 0);
+            assert_eq!(counters.available_bytes, initial_counters.available_bytes);
+            assert_eq!(counters.fragment_count, initial_counters.fragment_count);
+        }
+
+        // both heaps are entirely usable again
+        let ptr = unsafe { talc.malloc(layout).unwrap() };
+        assert!(meta_heap.contains(ptr.as_ptr()) || heap.contains(ptr.as_ptr()));
+        let count = 1 + core::iter::from_fn(|| unsafe { talc.malloc(layout) }.ok()).count();
+        assert_eq!(count, ptrs.len());
+
+        unsafe { talc.free_all(&[meta_heap, heap]) };
+        assert_eq!(talc.largest_allocatable(ALIGN), initial_largest);
+    }
+
+    #[test]
+    fn extend_overflow_test() {
+        let mut arena = [0u8; 10000];
+        let arena_span = Span::from(&mut arena);
+
+        let mut talc = Talc::new(crate::ErrOnOom);
+        let heap = unsafe { talc.claim(arena_span.truncate(2000, 2000)).unwrap() };
+
+        // requesting the current extent is a no-op
+        assert_eq!(unsafe { talc.extend(heap, heap) }, heap);
+
+        // an acme computed with wrapping arithmetic is rejected before anything is touched
+        let (base, acme) = heap.get_base_acme().unwrap();
+        for wrapped in [Span::new(base, acme.wrapping_add(usize::MAX)), Span::new(acme, base)] {
+            let mut talc = std::panic::AssertUnwindSafe(&mut talc);
+            let result = std::panic::catch_unwind(move || unsafe { talc.extend(heap, wrapped) });
+            assert!(result.is_err());
+        }
+
+        // saturating instead reaches the top of the address space, aligned down
+        let top = heap.extend_high(usize::MAX).word_align_inward();
+        assert_eq!(top.get_base_acme().unwrap().1 as usize, usize::MAX & !(ALIGN - 1));
+
+        // the heap is intact, and can still be extended within the arena
+        let heap = unsafe { talc.extend(heap, top.fit_within(arena_span)) };
+        assert!(heap.size() > 6000);
+
+        let layout = Layout::from_size_align(4000, 8).unwrap();
+        let ptr = unsafe { talc.malloc(layout).unwrap() };
+        unsafe { talc.free(ptr, layout) };
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn stale_heap_test() {
+        let mut arena = [0u8; 10000];
+        let arena_span = Span::from(&mut arena);
+
+        let mut talc = Talc::new(crate::ErrOnOom);
+        let heap = unsafe { talc.claim(arena_span.truncate(3000, 3000)).unwrap() };
+
+        let layout = Layout::new::<[u64; 4]>();
+        let allocation = unsafe { talc.malloc(layout).unwrap() };
+
+        let extended_up = unsafe { talc.extend(heap, heap.extend(0, 1000)) };
+        let extended_down = unsafe { talc.extend(extended_up, extended_up.extend(1000, 0)) };
+        let truncated = unsafe { talc.truncate(extended_down, extended_down.truncate(500, 500)) };
+
+        // only the current heap is accepted
+        for stale in [heap, extended_up, extended_down] {
+            let talc = std::panic::AssertUnwindSafe(&mut talc);
+            let result =
+                std::panic::catch_unwind(move || unsafe { talc.get_allocated_span(stale) });
+            assert!(result.is_err());
+        }
+
+        unsafe {
+            assert!(talc.get_allocated_span(truncated).contains(allocation.as_ptr()));
+            talc.free(allocation, layout);
+        }
+    }
+
+    #[cfg(feature = "log-oom")]
+    #[test]
+    fn log_oom_test() {
+        use std::sync::Mutex;
+
+        struct TestLogger(Mutex<std::vec::Vec<std::string::String>>);
+
+        impl log::Log for TestLogger {
+            fn enabled(&self, metadata: &log::Metadata) -> bool {
+                metadata.level() <= log::Level::Warn
+            }
+
+            fn log(&self, record: &log::Record) {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+
+            fn flush(&self) {}
+        }
+
+        static LOGGER: TestLogger = TestLogger(Mutex::new(std::vec::Vec::new()));
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Warn);
+
+        let mut arena = [0u8; 10000];
+        let mut talc = Talc::new(crate::ErrOnOom);
+        unsafe { talc.claim(arena.as_mut().into()).unwrap() };
+
+        // other tests may run out of memory concurrently, so only count this test's warnings
+        let warnings =
+            || LOGGER.0.lock().unwrap().iter().filter(|log| log.contains("size: 23456")).count();
+
+        let layout = Layout::from_size_align(1000, 8).unwrap();
+        let ptr = unsafe { talc.malloc(layout).unwrap() };
+
+        let too_big = Layout::from_size_align(23456, 8).unwrap();
+        // reserving allocates nothing, so failing to isn't logged
+        assert!(!talc.reserve(too_big));
+        assert_eq!(warnings(), 0);
+        assert!(unsafe { talc.malloc(too_big) }.is_err());
+        assert_eq!(warnings(), 1);
+
+        unsafe { talc.free(ptr, layout) };
+    }
+
+    #[test]
+    fn claim_layout_test() {
+        let layout = Layout::from_size_align(4096, 64).unwrap();
+        let ptr = NonNull::new(unsafe { std::alloc::alloc(layout) }).unwrap();
+
+        // an aligned region is claimed in its entirety
+        let mut talc = Talc::new(crate::ErrOnOom);
+        let heap = unsafe { talc.claim_layout(ptr, layout).unwrap() };
+        assert_eq!(heap, Span::from_base_size(ptr.as_ptr(), layout.size()));
+
+        let allocation = unsafe { talc.malloc(Layout::new::<[u64; 8]>()).unwrap() };
+        assert!(heap.contains(allocation.as_ptr()));
+
+        unsafe { std::alloc::dealloc(ptr.as_ptr(), layout) };
+    }
+
+    #[cfg(feature = "counters")]
+    #[test]
+    fn max_claimed_bytes_test() {
+        /// Claims the next of a few heaps, counting the calls.
+        struct HeapsOnOom {
+            heaps: std::vec::Vec<Span>,
+            calls: usize,
+        }
+
+        impl OomHandler for HeapsOnOom {
+            fn handle_oom(talc: &mut Talc<Self>, _: Layout) -> Result<(), ()> {
+                talc.oom_handler.calls += 1;
+                let heap = talc.oom_handler.heaps.pop().ok_or(())?;
+                unsafe { talc.claim(heap).map(|_| ()) }
+            }
+        }
+
+        let mut arena = [0u8; 12000];
+        let arena_span = Span::from(&mut arena);
+        let heaps = (0..3).map(|i| arena_span.truncate(i * 4000, (2 - i) * 4000)).collect();
+
+        let mut talc = Talc::new(HeapsOnOom { heaps, calls: 0 });
+        assert_eq!(talc.max_claimed_bytes(), usize::MAX);
+        talc.set_max_claimed_bytes(9000);
+
+        let layout = Layout::from_size_align(2000, 8).unwrap();
+        let a = unsafe { talc.malloc(layout).unwrap() };
+        let b = unsafe { talc.malloc(layout).unwrap() };
+        assert_eq!(talc.oom_handler.calls, 2);
+        assert_eq!(talc.get_counters().claimed_bytes, 8000);
+
+        // claiming more for this would exceed the cap, so the handler isn't invoked
+        assert!(unsafe { talc.malloc(layout) }.is_err());
+        assert_eq!(talc.oom_handler.calls, 2);
+
+        talc.set_max_claimed_bytes(usize::MAX);
+        let c = unsafe { talc.malloc(layout).unwrap() };
+        assert_eq!(talc.oom_handler.calls, 3);
+
+        for ptr in [a, b, c] {
+            unsafe { talc.free(ptr, layout) };
+        }
+    }
+
+    #[test]
+    fn claim_truncate_extend_test() {
+        // not big enough to fit the metadata
+        let mut tiny_heap = [0u8; BIN_COUNT * WORD_SIZE / 2];
+        let tiny_heap_span: Span = Span::from(&mut tiny_heap);
+
+        // big enough with plenty of extra
+        let big_heap = Box::leak(vec![0u8; BIN_COUNT * WORD_SIZE + 100000].into_boxed_slice());
+        let big_heap_span = Span::from(big_heap.as_mut());
+
+        let mut talc = Talc::new(crate::ErrOnOom);
+
+        unsafe {
+            talc.claim(tiny_heap_span).unwrap_err();
+        }
+
+        assert!(talc.bins.is_null());
+        assert!(talc.availability_low == 0 && talc.availability_high == 0);
+
+        let alloc_big_heap = unsafe { talc.claim(big_heap_span).unwrap() };
+
+        assert!(!talc.bins.is_null());
+
+        let alloc_big_heap = unsafe {
+            talc.truncate(
+                alloc_big_heap,
+                alloc_big_heap.truncate(500, 500).fit_over(talc.get_allocated_span(alloc_big_heap)),
+            )
+        };
+
+        let _alloc_tiny_heap = unsafe { talc.claim(tiny_heap_span).unwrap() };
+
+        let allocation = unsafe {
+            let allocation = talc.malloc(Layout::new::<u128>()).unwrap();
+            allocation.as_ptr().write_bytes(0, Layout::new::<u128>().size());
+            allocation
+        };
+
+        let alloc_big_heap = unsafe {
+            talc.truncate(
+                alloc_big_heap,
+                alloc_big_heap
+                    .truncate(100000, 100000)
+                    .fit_over(talc.get_allocated_span(alloc_big_heap)),
+            )
+        };
+
+        let _ = unsafe {
             talc.extend(
                 alloc_big_heap,
                 alloc_big_heap.extend(10000, 10000).fit_within(big_heap_span),
-            );
-        }
+            )
+        };
 
         unsafe {
             talc.free(allocation, Layout::new::<u128>());
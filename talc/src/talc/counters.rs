@@ -6,6 +6,8 @@ pub struct Counters {
     pub allocation_count: usize,
     /// Total number of allocations.
     pub total_allocation_count: u64,
+    /// Peak number of simultaneously active allocations.
+    pub max_allocation_count: usize,
 
     /// Sum of active allocations' layouts' size.
     pub allocated_bytes: usize,
@@ -24,10 +26,27 @@ pub struct Counters {
     /// Total number of established heaps.
     pub total_heap_count: u64,
 
-    /// Sum of bytes actively claimed.
+    /// Sum of bytes actively claimed, i.e. the current footprint of all heaps.
+    ///
+    /// Increased by [`claim`](crate::Talc::claim) and [`extend`](crate::Talc::extend),
+    /// decreased by [`truncate`](crate::Talc::truncate).
     pub claimed_bytes: usize,
     /// Sum of bytes ever claimed. Reclaimed bytes included.
+    ///
+    /// This never decreases. See also [`total_released_bytes`](Counters::total_released_bytes).
     pub total_claimed_bytes: u64,
+
+    /// Sum of bytes ever absorbed below allocations due to alignment,
+    /// instead of being left as a gap, as the leftover was too small.
+    pub total_alignment_slack_bytes: u64,
+
+    /// Number of times an allocation was grown in place.
+    pub total_grow_in_place_count: u64,
+    /// Number of times an allocation was moved to reallocate it, copying its contents.
+    ///
+    /// Compare with [`total_grow_in_place_count`](Counters::total_grow_in_place_count)
+    /// to judge whether pre-sizing buffers is worthwhile.
+    pub total_relocation_count: u64,
 }
 
 impl Counters {
@@ -35,6 +54,7 @@ impl Counters {
         Self {
             allocation_count: 0,
             total_allocation_count: 0,
+            max_allocation_count: 0,
             allocated_bytes: 0,
             total_allocated_bytes: 0,
             available_bytes: 0,
@@ -43,6 +63,9 @@ impl Counters {
             total_heap_count: 0,
             claimed_bytes: 0,
             total_claimed_bytes: 0,
+            total_alignment_slack_bytes: 0,
+            total_grow_in_place_count: 0,
+            total_relocation_count: 0,
         }
     }
 
@@ -56,11 +79,69 @@ impl Counters {
         self.total_allocated_bytes - self.allocated_bytes as u64
     }
 
-    /// Returns the total number of claimed bytes released.
+    /// Returns the total number of claimed bytes released by [`truncate`](crate::Talc::truncate).
+    ///
+    /// Together with [`total_claimed_bytes`](Counters::total_claimed_bytes), this shows how
+    /// much memory has been handed back, e.g. to the operating system, over time.
     pub const fn total_released_bytes(&self) -> u64 {
         self.total_claimed_bytes - self.claimed_bytes as u64
     }
 
+    /// Returns the change in the counters since `baseline`, an earlier snapshot
+    /// of the same allocator's counters.
+    ///
+    /// If `baseline` is actually the later snapshot, the totals wrap around
+    /// instead of panicking, and the result is meaningless.
+    ///
+    /// Useful for attributing allocator activity to a region of code:
+    /// ```rust
+    /// # use talc::*;
+    /// # let mut arena = [0u8; 10000];
+    /// # let mut talc = Talc::new(ErrOnOom);
+    /// # unsafe { talc.claim(arena.as_mut().into()).unwrap(); }
+    /// let baseline = *talc.get_counters();
+    ///
+    /// let layout = core::alloc::Layout::new::<[u64; 4]>();
+    /// let allocation = unsafe { talc.malloc(layout).unwrap() };
+    ///
+    /// let delta = talc.get_counters().diff(&baseline);
+    /// assert_eq!(delta.allocation_count, 1);
+    /// assert_eq!(delta.allocated_bytes, layout.size() as isize);
+    /// # unsafe { talc.free(allocation, layout); }
+    /// ```
+    pub const fn diff(&self, baseline: &Counters) -> CountersDelta {
+        CountersDelta {
+            allocation_count: self.allocation_count.wrapping_sub(baseline.allocation_count)
+                as isize,
+            total_allocation_count: self
+                .total_allocation_count
+                .wrapping_sub(baseline.total_allocation_count),
+            max_allocation_count: self.max_allocation_count,
+            allocated_bytes: self.allocated_bytes.wrapping_sub(baseline.allocated_bytes) as isize,
+            total_allocated_bytes: self
+                .total_allocated_bytes
+                .wrapping_sub(baseline.total_allocated_bytes)
+                as i64,
+            available_bytes: self.available_bytes.wrapping_sub(baseline.available_bytes) as isize,
+            fragment_count: self.fragment_count.wrapping_sub(baseline.fragment_count) as isize,
+            heap_count: self.heap_count.wrapping_sub(baseline.heap_count) as isize,
+            total_heap_count: self.total_heap_count.wrapping_sub(baseline.total_heap_count),
+            claimed_bytes: self.claimed_bytes.wrapping_sub(baseline.claimed_bytes) as isize,
+            total_claimed_bytes: self
+                .total_claimed_bytes
+                .wrapping_sub(baseline.total_claimed_bytes),
+            total_alignment_slack_bytes: self
+                .total_alignment_slack_bytes
+                .wrapping_sub(baseline.total_alignment_slack_bytes),
+            total_grow_in_place_count: self
+                .total_grow_in_place_count
+                .wrapping_sub(baseline.total_grow_in_place_count),
+            total_relocation_count: self
+                .total_relocation_count
+                .wrapping_sub(baseline.total_relocation_count),
+        }
+    }
+
     pub(crate) fn account_register_gap(&mut self, size: usize) {
         self.available_bytes += size;
         self.fragment_count += 1;
@@ -76,6 +157,10 @@ impl Counters {
 
         self.total_allocation_count += 1;
         self.total_allocated_bytes += alloc_size as u64;
+
+        if self.allocation_count > self.max_allocation_count {
+            self.max_allocation_count = self.allocation_count;
+        }
     }
 
     pub(crate) fn account_dealloc(&mut self, alloc_size: usize) {
@@ -83,9 +168,18 @@ impl Counters {
         self.allocated_bytes -= alloc_size;
     }
 
+    pub(crate) fn account_alignment_slack(&mut self, slack_size: usize) {
+        self.total_alignment_slack_bytes += slack_size as u64;
+    }
+
     pub(crate) fn account_grow_in_place(&mut self, old_alloc_size: usize, new_alloc_size: usize) {
         self.allocated_bytes += new_alloc_size - old_alloc_size;
         self.total_allocated_bytes += (new_alloc_size - old_alloc_size) as u64;
+        self.total_grow_in_place_count += 1;
+    }
+
+    pub(crate) fn account_relocation(&mut self) {
+        self.total_relocation_count += 1;
     }
 
     pub(crate) fn account_shrink_in_place(&mut self, old_alloc_size: usize, new_alloc_size: usize) {
@@ -113,6 +207,65 @@ impl Counters {
 
         self.claimed_bytes -= old_claimed_size - new_claimed_size;
     }
+
+    pub(crate) fn account_free_all(&mut self) {
+        self.allocation_count = 0;
+        self.allocated_bytes = 0;
+        self.available_bytes = 0;
+        self.fragment_count = 0;
+    }
+
+    pub(crate) fn account_reset(&mut self) {
+        self.available_bytes = 0;
+        self.fragment_count = 0;
+        self.heap_count = 0;
+        self.claimed_bytes = 0;
+    }
+}
+
+/// The change in [`Counters`] between two snapshots. See [`Counters::diff`].
+///
+/// Running values are signed, as they may have decreased in the interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CountersDelta {
+    /// Change in the number of active allocations.
+    pub allocation_count: isize,
+    /// Number of allocations made.
+    pub total_allocation_count: u64,
+    /// The peak number of simultaneously active allocations so far, as of the later snapshot.
+    ///
+    /// The peak within the interval isn't tracked, so this is the all-time peak.
+    pub max_allocation_count: usize,
+
+    /// Change in the sum of active allocations' layouts' size.
+    pub allocated_bytes: isize,
+    /// Change in the sum of all allocations' layouts' maximum size.
+    ///
+    /// This can be negative, as in-place shrinks are discounted.
+    pub total_allocated_bytes: i64,
+
+    /// Change in the number of bytes available for allocation.
+    pub available_bytes: isize,
+    /// Change in the number of holes/gaps between allocations.
+    pub fragment_count: isize,
+
+    /// Change in the number of active established heaps.
+    pub heap_count: isize,
+    /// Number of heaps established.
+    pub total_heap_count: u64,
+
+    /// Change in the sum of bytes actively claimed.
+    pub claimed_bytes: isize,
+    /// Number of bytes claimed.
+    pub total_claimed_bytes: u64,
+
+    /// Number of bytes absorbed below allocations due to alignment.
+    pub total_alignment_slack_bytes: u64,
+
+    /// Number of allocations grown in place.
+    pub total_grow_in_place_count: u64,
+    /// Number of allocations moved to reallocate them.
+    pub total_relocation_count: u64,
 }
 
 impl core::fmt::Display for Counters {
@@ -121,21 +274,31 @@ impl core::fmt::Display for Counters {
             r#"Stat                 | Running Total       | Accumulative Total
 ---------------------|---------------------|--------------------
 # of Allocations     | {:>19} | {:>19}
+Peak Allocations     | {:>19} |                 N/A
 # of Allocated Bytes | {:>19} | {:>19}
 # of Available Bytes | {:>19} |                 N/A
 # of Claimed Bytes   | {:>19} | {:>19}
+# of Released Bytes  |                 N/A | {:>19}
 # of Heaps           | {:>19} | {:>19}
-# of Fragments       | {:>19} |                 N/A"#,
+# of Fragments       | {:>19} |                 N/A
+# of Alignment Slack |                 N/A | {:>19}
+# of In-Place Grows  |                 N/A | {:>19}
+# of Relocations     |                 N/A | {:>19}"#,
             self.allocation_count,
             self.total_allocation_count,
+            self.max_allocation_count,
             self.allocated_bytes,
             self.total_allocated_bytes,
             self.available_bytes,
             self.claimed_bytes,
             self.total_claimed_bytes,
+            self.total_released_bytes(),
             self.heap_count,
             self.total_heap_count,
-            self.fragment_count
+            self.fragment_count,
+            self.total_alignment_slack_bytes,
+            self.total_grow_in_place_count,
+            self.total_relocation_count
         ))
     }
 }
@@ -144,6 +307,26 @@ impl<O: super::OomHandler> super::Talc<O> {
     pub fn get_counters(&self) -> &Counters {
         &self.counters
     }
+
+    /// Count a reallocation that moved the allocation, for callers outside
+    /// of `Talc` that reallocate manually, such as [`Talck`](crate::Talck).
+    pub(crate) fn account_relocation(&mut self) {
+        self.counters.account_relocation();
+    }
+
+    /// Panics if any allocations haven't been freed.
+    ///
+    /// This is intended for tests and shutdown checks, where all memory allocated
+    /// from this allocator is expected to have been freed by now.
+    #[track_caller]
+    pub fn assert_no_leaks(&self) {
+        assert!(
+            self.counters.allocation_count == 0,
+            "{} allocations totalling {} bytes were leaked",
+            self.counters.allocation_count,
+            self.counters.allocated_bytes
+        );
+    }
 }
 
 #[cfg(test)]
@@ -212,6 +395,7 @@ mod tests {
         assert!(talc.get_counters().total_allocation_count == 1);
         assert!(talc.get_counters().fragment_count == 1);
 
+        let claimed_heap1 = heap1;
         let heap1 = unsafe { talc.truncate(heap1, talc.get_allocated_span(heap1)) };
 
         assert!(heap1.size() <= TAG_SIZE + WORD_SIZE * WORD_BITS * 2 + 64);
@@ -219,11 +403,133 @@ mod tests {
         assert!(talc.get_counters().claimed_bytes == heap1.size());
         assert!(talc.get_counters().overhead_bytes() == talc.get_counters().claimed_bytes);
         assert!(talc.get_counters().total_claimed_bytes == pre_alloc_claimed_bytes as _);
+        assert!(
+            talc.get_counters().total_released_bytes()
+                == (pre_alloc_claimed_bytes - heap1.size()) as _
+        );
         assert!(talc.get_counters().available_bytes == 0);
         assert!(talc.get_counters().allocated_bytes == 0);
         assert!(talc.get_counters().total_allocated_bytes == alloc_layout.size() as _);
         assert!(talc.get_counters().allocation_count == 0);
         assert!(talc.get_counters().total_allocation_count == 1);
         assert!(talc.get_counters().fragment_count == 0);
+
+        // reclaiming the truncated memory counts towards both
+        let extended_heap1 = unsafe { talc.extend(heap1, claimed_heap1) };
+
+        assert!(talc.get_counters().claimed_bytes == extended_heap1.size());
+        assert!(
+            talc.get_counters().total_claimed_bytes
+                == (pre_alloc_claimed_bytes + extended_heap1.size() - heap1.size()) as _
+        );
+    }
+
+    #[test]
+    fn test_alignment_slack() {
+        let mut arena = [0u8; 100000];
+
+        let mut talc = Talc::new(ErrOnOom);
+        unsafe { talc.claim(arena.as_mut().into()).unwrap() };
+
+        assert!(talc.get_counters().total_alignment_slack_bytes == 0);
+
+        // the slack below these allocations is at most one word, far
+        // too small to be a gap, so it's absorbed whenever it exists
+        let layout = Layout::from_size_align(WORD_SIZE, WORD_SIZE * 2).unwrap();
+        let allocations = [(); 4].map(|_| unsafe { talc.malloc(layout).unwrap() });
+
+        let slack = talc.get_counters().total_alignment_slack_bytes;
+        assert!(slack > 0);
+        assert!(slack % WORD_SIZE as u64 == 0);
+        assert!(slack <= (WORD_SIZE * allocations.len()) as u64);
+
+        for allocation in allocations {
+            unsafe { talc.free(allocation, layout) };
+        }
+
+        assert!(talc.get_counters().total_alignment_slack_bytes == slack);
+    }
+
+    #[test]
+    fn test_grow_in_place_and_relocation() {
+        let mut arena = [0u8; 100000];
+
+        let mut talc = Talc::new(ErrOnOom);
+        unsafe { talc.claim(arena.as_mut().into()).unwrap() };
+
+        let layout = Layout::new::<[usize; 4]>();
+        let mut ptr = unsafe { talc.malloc(layout).unwrap() };
+
+        // there's free memory above, so this grows in place
+        ptr = unsafe { talc.grow(ptr, layout, 100).unwrap() };
+        assert!(talc.get_counters().total_grow_in_place_count == 1);
+        assert!(talc.get_counters().total_relocation_count == 0);
+
+        // block growing in place, so the allocation has to move
+        let old_layout = Layout::from_size_align(100, layout.align()).unwrap();
+        let blocker = unsafe { talc.malloc(layout).unwrap() };
+        let baseline = *talc.get_counters();
+
+        ptr = unsafe { talc.grow(ptr, old_layout, 1000).unwrap() };
+        let delta = talc.get_counters().diff(&baseline);
+        assert!(delta.total_grow_in_place_count == 0);
+        assert!(delta.total_relocation_count == 1);
+
+        // moving to satisfy a stricter alignment, or on request, is a relocation too
+        let old_layout = Layout::from_size_align(1000, layout.align()).unwrap();
+        let misaligned = 2 << (ptr.as_ptr() as usize).trailing_zeros();
+        ptr = unsafe { talc.realloc_aligned(ptr, old_layout, 1000, misaligned).unwrap() };
+        let old_layout = Layout::from_size_align(1000, misaligned).unwrap();
+        ptr = unsafe { talc.relocate(ptr, old_layout, core::ptr::null_mut()).unwrap() };
+        let delta = talc.get_counters().diff(&baseline);
+        assert!(delta.total_relocation_count == 3);
+
+        unsafe {
+            talc.free(ptr, old_layout);
+            talc.free(blocker, layout);
+        }
+    }
+
+    #[test]
+    fn test_max_allocation_count() {
+        let mut arena = [0u8; 100000];
+
+        let mut talc = Talc::new(ErrOnOom);
+        unsafe { talc.claim(arena.as_mut().into()).unwrap() };
+
+        let layout = Layout::new::<[usize; 4]>();
+        let allocations = [(); 3].map(|_| unsafe { talc.malloc(layout).unwrap() });
+        assert!(talc.get_counters().max_allocation_count == 3);
+
+        for allocation in allocations {
+            unsafe { talc.free(allocation, layout) };
+        }
+
+        let baseline = *talc.get_counters();
+        let allocation = unsafe { talc.malloc(layout).unwrap() };
+        assert!(talc.get_counters().allocation_count == 1);
+        assert!(talc.get_counters().max_allocation_count == 3);
+        // the interval peaked below the earlier high-water mark, which is reported as is
+        assert!(talc.get_counters().diff(&baseline).max_allocation_count == 3);
+
+        unsafe { talc.free(allocation, layout) };
+    }
+
+    #[test]
+    fn test_assert_no_leaks() {
+        let mut arena = [0u8; 100000];
+
+        let mut talc = Talc::new(ErrOnOom);
+        unsafe { talc.claim(arena.as_mut().into()).unwrap() };
+        talc.assert_no_leaks();
+
+        let layout = Layout::new::<[usize; 4]>();
+        let allocation = unsafe { talc.malloc(layout).unwrap() };
+
+        let leaked = std::panic::catch_unwind(|| talc.assert_no_leaks());
+        assert!(leaked.is_err());
+
+        unsafe { talc.free(allocation, layout) };
+        talc.assert_no_leaks();
     }
 }
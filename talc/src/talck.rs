@@ -8,16 +8,23 @@ use core::{
     ptr::{null_mut, NonNull},
 };
 
+#[cfg(any(feature = "allocator", feature = "allocator-api2"))]
+use crate::ptr_utils::{dangling, is_aligned_to};
+
 #[cfg(feature = "allocator")]
 use core::alloc::{AllocError, Allocator};
 
 #[cfg(all(feature = "allocator-api2", not(feature = "allocator")))]
 use allocator_api2::alloc::{AllocError, Allocator};
 
-#[cfg(any(feature = "allocator", feature = "allocator-api2"))]
-pub(crate) fn is_aligned_to(ptr: *mut u8, align: usize) -> bool {
-    (ptr as usize).trailing_zeros() >= align.trailing_zeros()
-}
+#[cfg(any(feature = "atomic-counters", feature = "metrics"))]
+use core::mem::ManuallyDrop;
+
+#[cfg(any(feature = "atomic-counters", feature = "lock-metrics"))]
+use core::sync::atomic::AtomicUsize;
+
+#[cfg(any(feature = "atomic-counters", feature = "lock-metrics"))]
+use core::sync::atomic::Ordering as AtomicOrdering;
 
 const RELEASE_LOCK_ON_REALLOC_LIMIT: usize = 0x10000;
 
@@ -29,20 +36,97 @@ const RELEASE_LOCK_ON_REALLOC_LIMIT: usize = 0x10000;
 /// let talc = Talc::new(ErrOnOom);
 /// let talck = talc.lock::<spin::Mutex<()>>();
 /// ```
+///
+/// # Statics
+/// [`Talck::new`] and [`Talc::lock`] are `const` for any [`lock_api::RawMutex`], as every
+/// implementation provides [`INIT`](lock_api::RawMutex::INIT). Where the raw mutex should
+/// be constructed differently, use [`Talck::const_new`].
+///
+/// To be used in a `static`, such as the `#[global_allocator]`, `Talck` must be `Sync`,
+/// which requires `R: Sync` and `O: Send`. The OOM handler must also be constructed in
+/// `const`, e.g. [`ClaimOnOom::new`](crate::ClaimOnOom::new) is a `const fn`.
 #[derive(Debug)]
 pub struct Talck<R: lock_api::RawMutex, O: OomHandler> {
     mutex: lock_api::Mutex<R, Talc<O>>,
+
+    #[cfg(feature = "atomic-counters")]
+    /// Mirrors a few of the counters, for reading without locking.
+    atomic_counters: AtomicCounters,
+
+    #[cfg(feature = "metrics")]
+    /// The names the counters are reported under.
+    metric_names: MetricNames,
+
+    #[cfg(feature = "leak-attribution")]
+    /// The thread that made each live allocation.
+    leak_table: LeakTable,
+
+    #[cfg(feature = "lock-metrics")]
+    /// How often the lock was acquired, and contended.
+    lock_metrics: AtomicLockMetrics,
 }
 
 impl<R: lock_api::RawMutex, O: OomHandler> Talck<R, O> {
-    /// Create a new `Talck`.
+    /// Create a new `Talck`, wrapping `talc`.
+    ///
+    /// The `Talc` may already be configured, e.g. with heaps claimed,
+    /// before being wrapped. See also [`Talc::lock`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use talc::*;
+    /// let mut arena = [0u8; 10000];
+    ///
+    /// let mut talc = Talc::new(ErrOnOom);
+    /// unsafe { talc.claim(arena.as_mut().into()).unwrap(); }
+    ///
+    /// let talck = Talck::<spin::Mutex<()>, _>::new(talc);
+    /// ```
     pub const fn new(talc: Talc<O>) -> Self {
-        Self { mutex: lock_api::Mutex::new(talc) }
+        Self::const_new(R::INIT, talc)
+    }
+
+    /// Create a new `Talck`, wrapping `talc` with an already-constructed raw mutex.
+    ///
+    /// This is useful where the raw mutex should be constructed other than by
+    /// [`INIT`](lock_api::RawMutex::INIT), such as in `const` contexts.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use talc::*;
+    /// use core::ptr::addr_of_mut;
+    ///
+    /// static mut ARENA: [u8; 10000] = [0; 10000];
+    /// static TALCK: Talck<spin::Mutex<()>, ClaimOnOom> = Talck::const_new(
+    ///     spin::Mutex::new(()),
+    ///     Talc::new(unsafe { ClaimOnOom::new(Span::from_array(addr_of_mut!(ARENA))) }),
+    /// );
+    ///
+    /// let layout = core::alloc::Layout::new::<u64>();
+    /// let ptr = unsafe { TALCK.lock().malloc(layout).unwrap() };
+    /// unsafe { TALCK.lock().free(ptr, layout) };
+    /// ```
+    pub const fn const_new(raw_mutex: R, talc: Talc<O>) -> Self {
+        Self {
+            mutex: lock_api::Mutex::const_new(raw_mutex, talc),
+
+            #[cfg(feature = "atomic-counters")]
+            atomic_counters: AtomicCounters::new(),
+
+            #[cfg(feature = "metrics")]
+            metric_names: MetricNames::DEFAULT,
+
+            #[cfg(feature = "leak-attribution")]
+            leak_table: LeakTable::new(),
+
+            #[cfg(feature = "lock-metrics")]
+            lock_metrics: AtomicLockMetrics::new(),
+        }
     }
 
     /// Lock the mutex and access the inner `Talc`.
     pub fn lock(&self) -> lock_api::MutexGuard<R, Talc<O>> {
-        self.mutex.lock()
+        self.lock_mutex()
     }
 
     /// Try to lock the mutex and access the inner `Talc`.
@@ -54,25 +138,147 @@ impl<R: lock_api::RawMutex, O: OomHandler> Talck<R, O> {
     pub fn into_inner(self) -> Talc<O> {
         self.mutex.into_inner()
     }
+
+    /// Access the inner `Talc` without locking, as `self` is exclusively borrowed.
+    pub fn get_mut(&mut self) -> &mut Talc<O> {
+        self.mutex.get_mut()
+    }
+
+    /// Retrieve the inner `Talc`'s OOM handler.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use talc::*;
+    /// let mut arena = [0u8; 10000];
+    /// let talck = Talc::new(unsafe { ClaimOnOom::new(Span::from(&mut arena)) })
+    ///     .lock::<spin::Mutex<()>>();
+    ///
+    /// let _claim_on_oom: ClaimOnOom = talck.into_oom_handler();
+    /// ```
+    pub fn into_oom_handler(self) -> O {
+        self.into_inner().oom_handler
+    }
+
+    /// Access the inner `Talc`'s OOM handler without locking, as `self` is exclusively borrowed.
+    pub fn oom_handler_mut(&mut self) -> &mut O {
+        &mut self.get_mut().oom_handler
+    }
+
+    /// Lock the mutex for an allocator operation, keeping the lock-free counters
+    /// and metrics up to date.
+    #[cfg(any(feature = "atomic-counters", feature = "metrics"))]
+    #[inline]
+    fn lock_alloc(&self) -> CountersSyncGuard<'_, R, O> {
+        CountersSyncGuard { guard: ManuallyDrop::new(self.lock_mutex()), talck: self }
+    }
+
+    /// Lock the mutex for an allocator operation.
+    #[cfg(not(any(feature = "atomic-counters", feature = "metrics")))]
+    #[inline]
+    fn lock_alloc(&self) -> lock_api::MutexGuard<'_, R, Talc<O>> {
+        self.lock_mutex()
+    }
+
+    /// Lock the mutex, counting whether it was contended with the `"lock-metrics"` feature.
+    #[inline]
+    fn lock_mutex(&self) -> lock_api::MutexGuard<'_, R, Talc<O>> {
+        #[cfg(feature = "lock-metrics")]
+        {
+            self.lock_metrics.total_locks.fetch_add(1, AtomicOrdering::Relaxed);
+
+            if let Some(guard) = self.mutex.try_lock() {
+                return guard;
+            }
+
+            self.lock_metrics.contended_locks.fetch_add(1, AtomicOrdering::Relaxed);
+        }
+
+        self.mutex.lock()
+    }
+}
+
+impl<R: lock_api::RawMutexTimed, O: OomHandler> Talck<R, O> {
+    /// Try to lock the mutex and access the inner `Talc`, giving up after `timeout`.
+    ///
+    /// Returns `None` if the lock couldn't be acquired in time,
+    /// which may be treated like an allocation failure.
+    pub fn try_lock_for(
+        &self,
+        timeout: R::Duration,
+    ) -> Option<lock_api::MutexGuard<'_, R, Talc<O>>> {
+        self.mutex.try_lock_for(timeout)
+    }
+
+    /// Try to lock the mutex and access the inner `Talc`, giving up once `timeout` is reached.
+    ///
+    /// Returns `None` if the lock couldn't be acquired in time,
+    /// which may be treated like an allocation failure.
+    pub fn try_lock_until(
+        &self,
+        timeout: R::Instant,
+    ) -> Option<lock_api::MutexGuard<'_, R, Talc<O>>> {
+        self.mutex.try_lock_until(timeout)
+    }
 }
 
 unsafe impl<R: lock_api::RawMutex, O: OomHandler> GlobalAlloc for Talck<R, O> {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-        self.lock().malloc(layout).map_or(null_mut(), |nn| nn.as_ptr())
+        let ptr = self.lock_alloc().malloc(layout).map_or(null_mut(), |nn| nn.as_ptr());
+
+        #[cfg(feature = "leak-attribution")]
+        self.leak_table.record(ptr, None);
+
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        // this is what the default implementation does too, but spell out that the lock
+        // is released by `alloc` before zeroing, so zeroing doesn't hold up other threads
+        let ptr = self.alloc(layout);
+
+        if !ptr.is_null() {
+            ptr.write_bytes(0, layout.size());
+        }
+
+        ptr
     }
 
     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
-        self.lock().free(NonNull::new_unchecked(ptr), layout)
+        // forget first, as the memory may be reallocated by another thread once freed
+        #[cfg(feature = "leak-attribution")]
+        self.leak_table.forget(ptr);
+
+        self.lock_alloc().free(NonNull::new_unchecked(ptr), layout)
     }
 
     unsafe fn realloc(&self, ptr: *mut u8, old_layout: Layout, new_size: usize) -> *mut u8 {
+        #[cfg(feature = "leak-attribution")]
+        let thread = self.leak_table.forget(ptr);
+
+        let new_ptr = self.realloc_untracked(ptr, old_layout, new_size);
+
+        #[cfg(feature = "leak-attribution")]
+        self.leak_table.record(if new_ptr.is_null() { ptr } else { new_ptr }, thread);
+
+        new_ptr
+    }
+}
+
+impl<R: lock_api::RawMutex, O: OomHandler> Talck<R, O> {
+    /// [`GlobalAlloc::realloc`], without updating the leak attribution records.
+    unsafe fn realloc_untracked(
+        &self,
+        ptr: *mut u8,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> *mut u8 {
         let nn_ptr = NonNull::new_unchecked(ptr);
 
         match new_size.cmp(&old_layout.size()) {
             Ordering::Greater => {
                 // first try to grow in-place before manually re-allocating
 
-                if let Ok(nn) = self.lock().grow_in_place(nn_ptr, old_layout, new_size) {
+                if let Ok(nn) = self.lock_alloc().grow_in_place(nn_ptr, old_layout, new_size) {
                     return nn.as_ptr();
                 }
 
@@ -80,7 +286,13 @@ unsafe impl<R: lock_api::RawMutex, O: OomHandler> GlobalAlloc for Talck<R, O> {
 
                 let new_layout = Layout::from_size_align_unchecked(new_size, old_layout.align());
 
-                let mut lock = self.lock();
+                let mut lock = self.lock_alloc();
+
+                #[cfg(feature = "realloc-shift-down")]
+                if let Ok(nn) = lock.grow_shift_down(nn_ptr, old_layout, new_size) {
+                    return nn.as_ptr();
+                }
+
                 let allocation = match lock.malloc(new_layout) {
                     Ok(ptr) => ptr,
                     Err(_) => return null_mut(),
@@ -89,17 +301,21 @@ unsafe impl<R: lock_api::RawMutex, O: OomHandler> GlobalAlloc for Talck<R, O> {
                 if old_layout.size() > RELEASE_LOCK_ON_REALLOC_LIMIT {
                     drop(lock);
                     allocation.as_ptr().copy_from_nonoverlapping(ptr, old_layout.size());
-                    lock = self.lock();
+                    lock = self.lock_alloc();
                 } else {
                     allocation.as_ptr().copy_from_nonoverlapping(ptr, old_layout.size());
                 }
 
                 lock.free(nn_ptr, old_layout);
+
+                #[cfg(feature = "counters")]
+                lock.account_relocation();
+
                 allocation.as_ptr()
             }
 
             Ordering::Less => {
-                self.lock().shrink(NonNull::new_unchecked(ptr), old_layout, new_size);
+                self.lock_alloc().shrink(NonNull::new_unchecked(ptr), old_layout, new_size);
                 ptr
             }
 
@@ -114,21 +330,64 @@ fn nonnull_slice_from_raw_parts(ptr: NonNull<u8>, len: usize) -> NonNull<[u8]> {
     unsafe { NonNull::new_unchecked(core::ptr::slice_from_raw_parts_mut(ptr.as_ptr(), len)) }
 }
 
+/// Round `layout`'s size up to a multiple of the word size.
+///
+/// Allocations are word-aligned, so this padding always belongs to the allocation, and
+/// any size that [fits](core::alloc::Allocator#memory-fitting) the padded allocation
+/// rounds up to the same size, finding the same tag. Larger sizes wouldn't be sound.
+#[cfg(feature = "allocate-at-least")]
+fn word_padded(layout: Layout) -> Option<Layout> {
+    let size = layout.size().checked_add(crate::ptr_utils::ALIGN - 1)?;
+    Layout::from_size_align(size & !(crate::ptr_utils::ALIGN - 1), layout.align()).ok()
+}
+
+/// With the `"allocator"` feature, this is the nightly [`core::alloc::Allocator`] trait,
+/// so `&Talck` can be used with the standard library's collections directly.
+/// Otherwise, with the `"allocator-api2"` feature, this is `allocator_api2`'s trait.
+///
+/// As with [`GlobalAlloc`], the [`OomHandler`] is invoked before an allocation fails,
+/// so it can recover memory (e.g. by freeing caches) for both traits. Only once it
+/// returns `Err` is [`AllocError`] returned.
+///
+/// # Example
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # use talc::*;
+/// let mut arena = [0u8; 10000];
+/// let talck = Talc::new(ErrOnOom).lock::<spin::Mutex<()>>();
+/// unsafe { talck.lock().claim(arena.as_mut().into()).unwrap(); }
+///
+/// let mut vec = std::vec::Vec::new_in(&talck);
+/// vec.extend(0..100usize);
+/// assert!(vec.iter().copied().eq(0..100));
+/// ```
 #[cfg(any(feature = "allocator", feature = "allocator-api2"))]
 unsafe impl<R: lock_api::RawMutex, O: OomHandler> Allocator for Talck<R, O> {
     fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        #[cfg(feature = "allocate-at-least")]
+        let layout = word_padded(layout).ok_or(AllocError)?;
+
         if layout.size() == 0 {
-            return Ok(nonnull_slice_from_raw_parts(NonNull::dangling(), 0));
+            return Ok(nonnull_slice_from_raw_parts(dangling(layout.align()), 0));
         }
 
-        unsafe { self.lock().malloc(layout) }
-            .map(|nn| nonnull_slice_from_raw_parts(nn, layout.size()))
-            .map_err(|_| AllocError)
+        let ptr = unsafe { self.lock_alloc().malloc(layout) }.map_err(|_| AllocError)?;
+
+        #[cfg(feature = "leak-attribution")]
+        self.leak_table.record(ptr.as_ptr(), None);
+
+        Ok(nonnull_slice_from_raw_parts(ptr, layout.size()))
     }
 
     unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        #[cfg(feature = "allocate-at-least")]
+        let layout = word_padded(layout).unwrap_or(layout);
+
         if layout.size() != 0 {
-            self.lock().free(ptr, layout);
+            #[cfg(feature = "leak-attribution")]
+            self.leak_table.forget(ptr.as_ptr());
+
+            self.lock_alloc().free(ptr, layout);
         }
     }
 
@@ -138,33 +397,25 @@ unsafe impl<R: lock_api::RawMutex, O: OomHandler> Allocator for Talck<R, O> {
         old_layout: Layout,
         new_layout: Layout,
     ) -> Result<NonNull<[u8]>, AllocError> {
-        debug_assert!(new_layout.size() >= old_layout.size());
+        #[cfg(feature = "allocate-at-least")]
+        let (old_layout, new_layout) = (
+            word_padded(old_layout).unwrap_or(old_layout),
+            word_padded(new_layout).ok_or(AllocError)?,
+        );
 
-        if old_layout.size() == 0 {
-            return self.allocate(new_layout);
-        } else if is_aligned_to(ptr.as_ptr(), new_layout.align()) {
-            // alignment is fine, try to allocate in-place
-            if let Ok(nn) = self.lock().grow_in_place(ptr, old_layout, new_layout.size()) {
-                return Ok(nonnull_slice_from_raw_parts(nn, new_layout.size()));
-            }
-        }
+        #[cfg(feature = "leak-attribution")]
+        let thread = self.leak_table.forget(ptr.as_ptr());
 
-        // can't grow in place, reallocate manually
+        let result = self.grow_untracked(ptr, old_layout, new_layout);
 
-        let mut lock = self.lock();
-        let allocation = lock.malloc(new_layout).map_err(|_| AllocError)?;
-
-        if old_layout.size() > RELEASE_LOCK_ON_REALLOC_LIMIT {
-            drop(lock);
-            allocation.as_ptr().copy_from_nonoverlapping(ptr.as_ptr(), old_layout.size());
-            lock = self.lock();
-        } else {
-            allocation.as_ptr().copy_from_nonoverlapping(ptr.as_ptr(), old_layout.size());
+        #[cfg(feature = "leak-attribution")]
+        match result {
+            Ok(allocation) => self.leak_table.record(allocation.as_ptr().cast(), thread),
+            Err(_) if old_layout.size() != 0 => self.leak_table.record(ptr.as_ptr(), thread),
+            Err(_) => (),
         }
 
-        lock.free(ptr, old_layout);
-
-        Ok(nonnull_slice_from_raw_parts(allocation, new_layout.size()))
+        result
     }
 
     unsafe fn grow_zeroed(
@@ -180,7 +431,7 @@ unsafe impl<R: lock_api::RawMutex, O: OomHandler> Allocator for Talck<R, O> {
                 .as_ptr()
                 .cast::<u8>()
                 .add(old_layout.size())
-                .write_bytes(0, new_layout.size() - old_layout.size());
+                .write_bytes(0, allocation.len() - old_layout.size());
         }
 
         res
@@ -191,34 +442,118 @@ unsafe impl<R: lock_api::RawMutex, O: OomHandler> Allocator for Talck<R, O> {
         ptr: NonNull<u8>,
         old_layout: Layout,
         new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        #[cfg(feature = "allocate-at-least")]
+        let (old_layout, new_layout) = (
+            word_padded(old_layout).unwrap_or(old_layout),
+            word_padded(new_layout).ok_or(AllocError)?,
+        );
+
+        #[cfg(feature = "leak-attribution")]
+        let thread = self.leak_table.forget(ptr.as_ptr());
+
+        let result = self.shrink_untracked(ptr, old_layout, new_layout);
+
+        #[cfg(feature = "leak-attribution")]
+        match result {
+            Ok(allocation) if new_layout.size() != 0 => {
+                self.leak_table.record(allocation.as_ptr().cast(), thread)
+            }
+            Ok(_) => (),
+            Err(_) => self.leak_table.record(ptr.as_ptr(), thread),
+        }
+
+        result
+    }
+}
+
+#[cfg(any(feature = "allocator", feature = "allocator-api2"))]
+impl<R: lock_api::RawMutex, O: OomHandler> Talck<R, O> {
+    /// [`Allocator::grow`], without updating the leak attribution records.
+    unsafe fn grow_untracked(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        debug_assert!(new_layout.size() >= old_layout.size());
+
+        if old_layout.size() == 0 {
+            return self.allocate(new_layout);
+        } else if is_aligned_to(ptr.as_ptr(), new_layout.align()) {
+            // alignment is fine, try to allocate in-place
+            if let Ok(nn) = self.lock_alloc().grow_in_place(ptr, old_layout, new_layout.size()) {
+                return Ok(nonnull_slice_from_raw_parts(nn, new_layout.size()));
+            }
+        }
+
+        // can't grow in place, reallocate manually
+
+        let mut lock = self.lock_alloc();
+
+        #[cfg(feature = "realloc-shift-down")]
+        if new_layout.align() <= old_layout.align() {
+            if let Ok(nn) = lock.grow_shift_down(ptr, old_layout, new_layout.size()) {
+                return Ok(nonnull_slice_from_raw_parts(nn, new_layout.size()));
+            }
+        }
+
+        let allocation = lock.malloc(new_layout).map_err(|_| AllocError)?;
+
+        if old_layout.size() > RELEASE_LOCK_ON_REALLOC_LIMIT {
+            drop(lock);
+            allocation.as_ptr().copy_from_nonoverlapping(ptr.as_ptr(), old_layout.size());
+            lock = self.lock_alloc();
+        } else {
+            allocation.as_ptr().copy_from_nonoverlapping(ptr.as_ptr(), old_layout.size());
+        }
+
+        lock.free(ptr, old_layout);
+
+        #[cfg(feature = "counters")]
+        lock.account_relocation();
+
+        Ok(nonnull_slice_from_raw_parts(allocation, new_layout.size()))
+    }
+
+    /// [`Allocator::shrink`], without updating the leak attribution records.
+    unsafe fn shrink_untracked(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
     ) -> Result<NonNull<[u8]>, AllocError> {
         debug_assert!(new_layout.size() <= old_layout.size());
 
         if new_layout.size() == 0 {
             if old_layout.size() > 0 {
-                self.lock().free(ptr, old_layout);
+                self.lock_alloc().free(ptr, old_layout);
             }
 
-            return Ok(nonnull_slice_from_raw_parts(NonNull::dangling(), 0));
+            return Ok(nonnull_slice_from_raw_parts(dangling(new_layout.align()), 0));
         }
 
         if !is_aligned_to(ptr.as_ptr(), new_layout.align()) {
-            let mut lock = self.lock();
+            let mut lock = self.lock_alloc();
             let allocation = lock.malloc(new_layout).map_err(|_| AllocError)?;
 
             if new_layout.size() > RELEASE_LOCK_ON_REALLOC_LIMIT {
                 drop(lock);
                 allocation.as_ptr().copy_from_nonoverlapping(ptr.as_ptr(), new_layout.size());
-                lock = self.lock();
+                lock = self.lock_alloc();
             } else {
                 allocation.as_ptr().copy_from_nonoverlapping(ptr.as_ptr(), new_layout.size());
             }
 
             lock.free(ptr, old_layout);
+
+            #[cfg(feature = "counters")]
+            lock.account_relocation();
+
             return Ok(nonnull_slice_from_raw_parts(allocation, new_layout.size()));
         }
 
-        self.lock().shrink(ptr, old_layout, new_layout.size());
+        self.lock_alloc().shrink(ptr, old_layout, new_layout.size());
 
         Ok(nonnull_slice_from_raw_parts(ptr, new_layout.size()))
     }
@@ -247,6 +582,384 @@ impl<O: OomHandler> Talc<O> {
     }
 }
 
+#[cfg(feature = "atomic-counters")]
+impl<R: lock_api::RawMutex, O: OomHandler> Talck<R, O> {
+    /// Read a snapshot of the most important counters without locking.
+    ///
+    /// These are updated by the [`GlobalAlloc`] and [`Allocator`](core::alloc::Allocator)
+    /// implementations before the lock is released. The values may be slightly stale, and
+    /// operations done directly on the [`Talc`] via [`lock`](Talck::lock) are only reflected
+    /// once a subsequent allocator operation completes.
+    ///
+    /// Use [`Talc::get_counters`] for precise and complete statistics.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use talc::*;
+    /// # use core::alloc::{GlobalAlloc, Layout};
+    /// let mut arena = [0u8; 10000];
+    /// let talck = Talc::new(ErrOnOom).lock::<spin::Mutex<()>>();
+    /// unsafe { talck.lock().claim(arena.as_mut().into()).unwrap(); }
+    ///
+    /// let layout = Layout::new::<[u64; 4]>();
+    /// let allocation = unsafe { talck.alloc(layout) };
+    ///
+    /// assert_eq!(talck.lock_free_counters().allocation_count, 1);
+    /// assert_eq!(talck.lock_free_counters().allocated_bytes, layout.size());
+    /// # unsafe { talck.dealloc(allocation, layout); }
+    /// ```
+    pub fn lock_free_counters(&self) -> LockFreeCounters {
+        LockFreeCounters {
+            allocation_count: self.atomic_counters.allocation_count.load(AtomicOrdering::Relaxed),
+            allocated_bytes: self.atomic_counters.allocated_bytes.load(AtomicOrdering::Relaxed),
+            claimed_bytes: self.atomic_counters.claimed_bytes.load(AtomicOrdering::Relaxed),
+        }
+    }
+}
+
+/// A snapshot of counters that can be read without locking.
+/// See [`Talck::lock_free_counters`].
+#[cfg(feature = "atomic-counters")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct LockFreeCounters {
+    /// Number of active allocations.
+    pub allocation_count: usize,
+    /// Sum of active allocations' layouts' size.
+    pub allocated_bytes: usize,
+    /// Sum of bytes actively claimed.
+    pub claimed_bytes: usize,
+}
+
+#[cfg(feature = "atomic-counters")]
+#[derive(Debug)]
+struct AtomicCounters {
+    allocation_count: AtomicUsize,
+    allocated_bytes: AtomicUsize,
+    claimed_bytes: AtomicUsize,
+}
+
+#[cfg(feature = "atomic-counters")]
+impl AtomicCounters {
+    const fn new() -> Self {
+        Self {
+            allocation_count: AtomicUsize::new(0),
+            allocated_bytes: AtomicUsize::new(0),
+            claimed_bytes: AtomicUsize::new(0),
+        }
+    }
+}
+
+#[cfg(feature = "lock-metrics")]
+impl<R: lock_api::RawMutex, O: OomHandler> Talck<R, O> {
+    /// Read how often the lock has been acquired, and how often it was contended.
+    ///
+    /// Every [`lock`](Talck::lock) and allocator operation is counted. A lock is contended
+    /// where it was already held, in which case the [`RawMutex`](lock_api::RawMutex) had to
+    /// spin or block. A high proportion of contended locks indicates that threads are
+    /// frequently waiting on each other to allocate.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use talc::*;
+    /// # use core::alloc::{GlobalAlloc, Layout};
+    /// let mut arena = [0u8; 10000];
+    /// let talck = Talc::new(ErrOnOom).lock::<spin::Mutex<()>>();
+    /// unsafe { talck.lock().claim(arena.as_mut().into()).unwrap(); }
+    ///
+    /// let layout = Layout::new::<[u64; 4]>();
+    /// unsafe { talck.dealloc(talck.alloc(layout), layout) };
+    ///
+    /// assert_eq!(talck.lock_metrics(), LockMetrics { total_locks: 3, contended_locks: 0 });
+    /// ```
+    pub fn lock_metrics(&self) -> LockMetrics {
+        LockMetrics {
+            total_locks: self.lock_metrics.total_locks.load(AtomicOrdering::Relaxed),
+            contended_locks: self.lock_metrics.contended_locks.load(AtomicOrdering::Relaxed),
+        }
+    }
+}
+
+/// How often a [`Talck`]'s lock was acquired. See [`Talck::lock_metrics`].
+#[cfg(feature = "lock-metrics")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct LockMetrics {
+    /// Number of times the lock was acquired.
+    pub total_locks: usize,
+    /// Number of times the lock was already held when acquiring it.
+    pub contended_locks: usize,
+}
+
+#[cfg(feature = "lock-metrics")]
+#[derive(Debug)]
+struct AtomicLockMetrics {
+    total_locks: AtomicUsize,
+    contended_locks: AtomicUsize,
+}
+
+#[cfg(feature = "lock-metrics")]
+impl AtomicLockMetrics {
+    const fn new() -> Self {
+        Self { total_locks: AtomicUsize::new(0), contended_locks: AtomicUsize::new(0) }
+    }
+}
+
+/// Updates the [`AtomicCounters`] from the [`Talc`]'s counters when dropped, before unlocking,
+/// and reports the [`Talck`]'s metrics after unlocking.
+#[cfg(any(feature = "atomic-counters", feature = "metrics"))]
+struct CountersSyncGuard<'a, R: lock_api::RawMutex, O: OomHandler> {
+    guard: ManuallyDrop<lock_api::MutexGuard<'a, R, Talc<O>>>,
+    talck: &'a Talck<R, O>,
+}
+
+#[cfg(any(feature = "atomic-counters", feature = "metrics"))]
+impl<'a, R: lock_api::RawMutex, O: OomHandler> core::ops::Deref for CountersSyncGuard<'a, R, O> {
+    type Target = Talc<O>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+#[cfg(any(feature = "atomic-counters", feature = "metrics"))]
+impl<'a, R: lock_api::RawMutex, O: OomHandler> core::ops::DerefMut for CountersSyncGuard<'a, R, O> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+#[cfg(any(feature = "atomic-counters", feature = "metrics"))]
+impl<'a, R: lock_api::RawMutex, O: OomHandler> Drop for CountersSyncGuard<'a, R, O> {
+    fn drop(&mut self) {
+        #[cfg(feature = "atomic-counters")]
+        {
+            let counters = self.guard.get_counters();
+            let atomics = &self.talck.atomic_counters;
+            atomics.allocation_count.store(counters.allocation_count, AtomicOrdering::Relaxed);
+            atomics.allocated_bytes.store(counters.allocated_bytes, AtomicOrdering::Relaxed);
+            atomics.claimed_bytes.store(counters.claimed_bytes, AtomicOrdering::Relaxed);
+        }
+
+        #[cfg(feature = "metrics")]
+        let counters = *self.guard.get_counters();
+
+        // SAFETY: the guard is not used again
+        unsafe { ManuallyDrop::drop(&mut self.guard) };
+
+        #[cfg(feature = "metrics")]
+        self.talck.report_metrics(&counters);
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl<R: lock_api::RawMutex, O: OomHandler> Talck<R, O> {
+    /// Report the counters under `names` instead of [`MetricNames::DEFAULT`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use talc::*;
+    /// static ALLOCATOR: Talck<spin::Mutex<()>, ErrOnOom> =
+    ///     Talc::new(ErrOnOom).lock().with_metric_names(MetricNames {
+    ///         allocated_bytes: "heap_allocated_bytes",
+    ///         ..MetricNames::DEFAULT
+    ///     });
+    /// ```
+    pub const fn with_metric_names(mut self, names: MetricNames) -> Self {
+        self.metric_names = names;
+        self
+    }
+
+    /// Get the names the counters are reported under.
+    pub fn metric_names(&self) -> &MetricNames {
+        &self.metric_names
+    }
+
+    fn report_metrics(&self, counters: &crate::Counters) {
+        // The recorder may allocate using this allocator, which would recurse back into here.
+        // Only this thread's nested reports are skipped, other threads still report theirs.
+        let _ = REPORTING_METRICS.try_with(|reporting| {
+            if reporting.replace(true) {
+                return;
+            }
+
+            let names = &self.metric_names;
+            metrics::gauge!(names.allocation_count).set(counters.allocation_count as f64);
+            metrics::gauge!(names.allocated_bytes).set(counters.allocated_bytes as f64);
+            metrics::gauge!(names.available_bytes).set(counters.available_bytes as f64);
+            metrics::gauge!(names.claimed_bytes).set(counters.claimed_bytes as f64);
+            metrics::counter!(names.total_allocation_count)
+                .absolute(counters.total_allocation_count);
+            metrics::counter!(names.total_allocated_bytes).absolute(counters.total_allocated_bytes);
+
+            reporting.set(false);
+        });
+    }
+}
+
+#[cfg(feature = "metrics")]
+std::thread_local! {
+    /// Set while this thread reports metrics, as the recorder may allocate in turn.
+    static REPORTING_METRICS: core::cell::Cell<bool> = const { core::cell::Cell::new(false) };
+}
+
+/// The names [`Talck`] reports its counters under to the [`metrics`] facade.
+///
+/// Gauges and counters are set to the [`Counters`](crate::Counters)' absolute values
+/// after each [`GlobalAlloc`] and [`Allocator`](core::alloc::Allocator) operation,
+/// once the lock has been released. See [`Talck::with_metric_names`].
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MetricNames {
+    /// Gauge of the number of active allocations.
+    pub allocation_count: &'static str,
+    /// Gauge of the sum of active allocations' layouts' size.
+    pub allocated_bytes: &'static str,
+    /// Gauge of the bytes available for allocation.
+    pub available_bytes: &'static str,
+    /// Gauge of the bytes actively claimed.
+    pub claimed_bytes: &'static str,
+    /// Counter of the number of allocations ever made.
+    pub total_allocation_count: &'static str,
+    /// Counter of the sum of all allocations' layouts' size.
+    pub total_allocated_bytes: &'static str,
+}
+
+#[cfg(feature = "metrics")]
+impl MetricNames {
+    /// The default names, prefixed with `talc_`.
+    pub const DEFAULT: Self = Self {
+        allocation_count: "talc_allocation_count",
+        allocated_bytes: "talc_allocated_bytes",
+        available_bytes: "talc_available_bytes",
+        claimed_bytes: "talc_claimed_bytes",
+        total_allocation_count: "talc_total_allocation_count",
+        total_allocated_bytes: "talc_total_allocated_bytes",
+    };
+}
+
+#[cfg(feature = "metrics")]
+impl Default for MetricNames {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(all(feature = "std", feature = "leak-attribution"))]
+impl<R: lock_api::RawMutex, O: OomHandler> Talck<R, O> {
+    /// Calls `f` with each live allocation and the thread that made it.
+    ///
+    /// Only allocations made via the [`GlobalAlloc`] and `Allocator` implementations
+    /// are recorded. Reallocated memory is attributed to the thread that first allocated it.
+    ///
+    /// The records are copied before `f` is called, so `f` may allocate.
+    /// Allocations made by `f` aren't recorded until it returns.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use talc::*;
+    /// # use core::alloc::{GlobalAlloc, Layout};
+    /// let mut arena = [0u8; 10000];
+    /// let talck = Talc::new(ErrOnOom).lock::<spin::Mutex<()>>();
+    /// unsafe { talck.lock().claim(arena.as_mut().into()).unwrap(); }
+    ///
+    /// let leaked = unsafe { talck.alloc(Layout::new::<u64>()) };
+    ///
+    /// talck.for_each_live_allocation(|ptr, thread| {
+    ///     assert_eq!((ptr.as_ptr(), thread), (leaked, std::thread::current().id()));
+    /// });
+    /// ```
+    pub fn for_each_live_allocation(&self, mut f: impl FnMut(NonNull<u8>, std::thread::ThreadId)) {
+        let records = self.leak_table.update(|table| {
+            table.iter().map(|(&addr, &thread)| (addr, thread)).collect::<std::vec::Vec<_>>()
+        });
+
+        for (addr, thread) in records.into_iter().flatten() {
+            // SAFETY: null pointers are never recorded
+            f(unsafe { NonNull::new_unchecked(addr as *mut u8) }, thread);
+        }
+    }
+}
+
+#[cfg(all(feature = "std", feature = "leak-attribution"))]
+std::thread_local! {
+    /// Set while this thread updates a [`LeakTable`], as that may allocate in turn.
+    static UPDATING_LEAK_TABLE: core::cell::Cell<bool> = const { core::cell::Cell::new(false) };
+
+    /// Cached, as this has no destructor, thus remains available while the thread exits.
+    static THREAD_ID: core::cell::Cell<Option<std::thread::ThreadId>> =
+        const { core::cell::Cell::new(None) };
+}
+
+/// Records the thread that made each live allocation, by address.
+///
+/// Updated only while [`Talck`]'s lock is released, as the table allocates.
+#[cfg(all(feature = "std", feature = "leak-attribution"))]
+#[derive(Debug)]
+struct LeakTable(std::sync::Mutex<std::collections::BTreeMap<usize, std::thread::ThreadId>>);
+
+#[cfg(all(feature = "std", feature = "leak-attribution"))]
+impl LeakTable {
+    const fn new() -> Self {
+        Self(std::sync::Mutex::new(std::collections::BTreeMap::new()))
+    }
+
+    /// Runs `f` on the table, unless the current thread is already updating it.
+    fn update<T>(
+        &self,
+        f: impl FnOnce(&mut std::collections::BTreeMap<usize, std::thread::ThreadId>) -> T,
+    ) -> Option<T> {
+        /// Clears the flag even if `f` unwinds.
+        struct Updating<'a>(&'a core::cell::Cell<bool>);
+
+        impl Drop for Updating<'_> {
+            fn drop(&mut self) {
+                self.0.set(false);
+            }
+        }
+
+        UPDATING_LEAK_TABLE
+            .try_with(|updating| {
+                if updating.replace(true) {
+                    return None;
+                }
+
+                let _updating = Updating(updating);
+                let mut table = self.0.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+                Some(f(&mut table))
+            })
+            .ok()
+            .flatten()
+    }
+
+    /// Records `ptr` as allocated by `thread`, or the current thread if `None`.
+    fn record(&self, ptr: *mut u8, thread: Option<std::thread::ThreadId>) {
+        if ptr.is_null() {
+            return;
+        }
+
+        self.update(|table| {
+            // looking up the current thread may allocate, so do so while updating
+            let thread = thread.or_else(|| {
+                THREAD_ID
+                    .try_with(|id| {
+                        let thread = id.get().unwrap_or_else(|| std::thread::current().id());
+                        id.set(Some(thread));
+                        thread
+                    })
+                    .ok()
+            });
+
+            if let Some(thread) = thread {
+                table.insert(ptr as usize, thread);
+            }
+        });
+    }
+
+    /// Forgets `ptr`, returning the thread that allocated it, if recorded.
+    fn forget(&self, ptr: *mut u8) -> Option<std::thread::ThreadId> {
+        self.update(|table| table.remove(&(ptr as usize))).flatten()
+    }
+}
+
 #[cfg(all(target_family = "wasm"))]
 impl TalckWasm {
     /// Create a [`Talck`] instance that takes control of WASM memory management.
@@ -260,5 +973,326 @@ impl TalckWasm {
     }
 }
 
+/// A [`Talck`] that manages WASM memory, see [`TalckWasm::new_global`].
+///
+/// Besides serving as the `#[global_allocator]`, with the `"allocator"` or `"allocator-api2"`
+/// feature this implements `Allocator` like any other [`Talck`]. A separate instance can thus
+/// act as a scoped allocator via `Vec::new_in(&talck)`, growing WASM memory independently of
+/// the global allocator.
 #[cfg(all(target_family = "wasm"))]
 pub type TalckWasm = Talck<crate::locking::AssumeUnlockable, crate::WasmHandler>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ErrOnOom, Span};
+
+    struct PanicOnOom;
+
+    impl OomHandler for PanicOnOom {
+        fn handle_oom(_: &mut Talc<Self>, _: Layout) -> Result<(), ()> {
+            panic!("out of memory");
+        }
+    }
+
+    #[cfg(feature = "lock-metrics")]
+    #[test]
+    fn lock_metrics_test() {
+        let talck = Talc::new(ErrOnOom).lock::<spin::Mutex<()>>();
+
+        drop(talck.lock());
+        assert_eq!(talck.lock_metrics(), LockMetrics { total_locks: 1, contended_locks: 0 });
+
+        let guard = talck.lock();
+        std::thread::scope(|scope| {
+            let waiter = scope.spawn(|| drop(talck.lock()));
+
+            // the waiter counts its lock before trying it, which fails as the guard is held
+            while talck.lock_metrics().total_locks < 3 {
+                std::hint::spin_loop();
+            }
+
+            drop(guard);
+            waiter.join().unwrap();
+        });
+
+        assert_eq!(talck.lock_metrics(), LockMetrics { total_locks: 3, contended_locks: 1 });
+    }
+
+    #[test]
+    fn oom_handler_panic_releases_lock() {
+        let talck = Talc::new(PanicOnOom).lock::<spin::Mutex<()>>();
+        let layout = Layout::new::<[u64; 8]>();
+
+        let alloc = std::panic::AssertUnwindSafe(|| unsafe { talck.alloc(layout) });
+        assert!(std::panic::catch_unwind(alloc).is_err());
+
+        // the lock was released while unwinding, and the allocator is still usable
+        let mut arena = [0u8; 10000];
+        unsafe { talck.try_lock().unwrap().claim(Span::from(&mut arena)).unwrap() };
+
+        let ptr = unsafe { talck.alloc(layout) };
+        assert!(!ptr.is_null());
+        unsafe { talck.dealloc(ptr, layout) };
+
+        // ErrOnOom just fails instead
+        let talck = Talc::new(ErrOnOom).lock::<spin::Mutex<()>>();
+        assert!(unsafe { talck.alloc(layout) }.is_null());
+        assert!(talck.try_lock().is_some());
+    }
+
+    #[test]
+    fn realloc_preserves_contents() {
+        let mut arena = [0u8; 10000];
+        let talck = Talc::new(ErrOnOom).lock::<spin::Mutex<()>>();
+        unsafe { talck.lock().claim(Span::from(&mut arena)).unwrap() };
+
+        let layout = Layout::from_size_align(16, 8).unwrap();
+        let mut ptr = unsafe { talck.alloc(layout) };
+        unsafe { ptr.write_bytes(0xab, 16) };
+
+        // block growing in place, so the contents have to be moved
+        let blocker = unsafe { talck.alloc(layout) };
+
+        for (old_size, new_size) in [(16, 500), (500, 40), (40, 2000), (2000, 16)] {
+            let old_layout = Layout::from_size_align(old_size, 8).unwrap();
+            ptr = unsafe { talck.realloc(ptr, old_layout, new_size) };
+            assert!(!ptr.is_null());
+
+            let bytes = unsafe { core::slice::from_raw_parts(ptr, 16) };
+            assert!(bytes.iter().all(|&b| b == 0xab));
+        }
+
+        unsafe { talck.dealloc(ptr, layout) };
+        unsafe { talck.dealloc(blocker, layout) };
+    }
+
+    #[test]
+    fn alloc_zeroed_while_other_threads_allocate() {
+        const SIZE: usize = 1 << 20;
+
+        let mut arena = std::vec![0u8; SIZE * 2];
+        let talck = Talc::new(ErrOnOom).lock::<spin::Mutex<()>>();
+        unsafe { talck.lock().claim(Span::from(arena.as_mut_slice())).unwrap() };
+
+        let big = Layout::from_size_align(SIZE, 8).unwrap();
+        let small = Layout::new::<[u64; 4]>();
+
+        // dirty the memory so that zeroing is observable, as other threads write to
+        // and free memory that the zeroed allocations may reuse
+        unsafe {
+            let ptr = talck.alloc(big);
+            ptr.write_bytes(0xff, SIZE);
+            talck.dealloc(ptr, big);
+        }
+
+        std::thread::scope(|scope| {
+            for _ in 0..2 {
+                scope.spawn(|| {
+                    for _ in 0..1000 {
+                        unsafe {
+                            let ptr = talck.alloc(small);
+                            assert!(!ptr.is_null());
+                            ptr.write_bytes(0xff, small.size());
+                            talck.dealloc(ptr, small);
+                        }
+                    }
+                });
+            }
+
+            for _ in 0..4 {
+                unsafe {
+                    let ptr = talck.alloc_zeroed(big);
+                    assert!(!ptr.is_null());
+                    assert!(core::slice::from_raw_parts(ptr, SIZE).iter().all(|&b| b == 0));
+                    ptr.write_bytes(0xff, SIZE);
+                    talck.dealloc(ptr, big);
+                }
+            }
+        });
+    }
+
+    #[cfg(all(feature = "allocate-at-least", feature = "allocator"))]
+    #[test]
+    fn allocate_at_least_test() {
+        use crate::ptr_utils::ALIGN;
+
+        let mut arena = [0u8; 10000];
+        let talck = Talc::new(ErrOnOom).lock::<spin::Mutex<()>>();
+        unsafe { talck.lock().claim(Span::from(&mut arena)).unwrap() };
+        let initial_largest = talck.lock().largest_allocatable(ALIGN);
+
+        for size in 1..=3 * ALIGN {
+            let layout = Layout::from_size_align(size, 1).unwrap();
+            let allocation = talck.allocate_zeroed(layout).unwrap();
+            let len = allocation.len();
+
+            // the padding up to the next word is included, and zeroed
+            assert!(len >= size && len < size + ALIGN && len % ALIGN == 0, "{} {}", size, len);
+            assert!(unsafe { allocation.as_ref() }.iter().all(|&b| b == 0));
+
+            // the whole slice may be used to grow or deallocate
+            let fit = Layout::from_size_align(len, 1).unwrap();
+            let new_layout = Layout::new::<[u8; 99]>();
+            let grown = unsafe { talck.grow_zeroed(allocation.cast(), fit, new_layout).unwrap() };
+            assert!(grown.len() >= new_layout.size());
+            assert!(unsafe { grown.as_ref() }.iter().all(|&b| b == 0));
+
+            let fit = Layout::from_size_align(grown.len(), 1).unwrap();
+            unsafe { talck.deallocate(grown.cast(), fit) };
+        }
+
+        assert_eq!(talck.lock().largest_allocatable(ALIGN), initial_largest);
+    }
+
+    #[cfg(any(feature = "allocator", feature = "allocator-api2"))]
+    #[test]
+    fn zero_size_allocations_are_aligned() {
+        let talck = Talc::new(ErrOnOom).lock::<spin::Mutex<()>>();
+
+        let layout = Layout::from_size_align(0, 64).unwrap();
+        let allocation = talck.allocate(layout).unwrap();
+        assert!(is_aligned_to(allocation.as_ptr().cast(), 64));
+        unsafe { talck.deallocate(allocation.cast(), layout) };
+    }
+
+    #[cfg(any(feature = "allocator", feature = "allocator-api2"))]
+    #[test]
+    fn allocator_invokes_oom_handler() {
+        /// Frees cached allocations to recover from OOM.
+        struct FreeCacheOnOom {
+            cache: std::vec::Vec<(NonNull<u8>, Layout)>,
+            calls: usize,
+        }
+
+        impl OomHandler for FreeCacheOnOom {
+            fn handle_oom(talc: &mut Talc<Self>, _: Layout) -> Result<(), ()> {
+                talc.oom_handler.calls += 1;
+                let (ptr, layout) = talc.oom_handler.cache.pop().ok_or(())?;
+                unsafe { talc.free(ptr, layout) };
+                Ok(())
+            }
+        }
+
+        let mut arena = [0u8; 10000];
+        let handler = FreeCacheOnOom { cache: std::vec::Vec::new(), calls: 0 };
+        let talck = Talc::new(handler).lock::<spin::Mutex<()>>();
+        unsafe { talck.lock().claim(Span::from(&mut arena)).unwrap() };
+
+        let layout = Layout::from_size_align(4000, 8).unwrap();
+        let cached = talck.allocate(layout).unwrap().cast();
+        talck.lock().oom_handler.cache.push((cached, layout));
+        let held = talck.allocate(layout).unwrap().cast();
+
+        // only fits once the cache is freed
+        let retried = talck.allocate(layout).unwrap().cast();
+        assert_eq!(talck.lock().oom_handler.calls, 1);
+
+        // nothing left to free
+        assert!(talck.allocate(layout).is_err());
+        assert_eq!(talck.lock().oom_handler.calls, 2);
+
+        unsafe { talck.deallocate(held, layout) };
+        unsafe { talck.deallocate(retried, layout) };
+    }
+
+    #[cfg(feature = "leak-attribution")]
+    #[test]
+    fn leak_attribution_test() {
+        let mut arena = [0u8; 10000];
+        let talck = Talc::new(ErrOnOom).lock::<spin::Mutex<()>>();
+        unsafe { talck.lock().claim(Span::from(&mut arena)).unwrap() };
+
+        let layout = Layout::new::<[u64; 4]>();
+
+        let (leaked, leaker) = std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    let freed = unsafe { talck.alloc(layout) };
+                    let leaked = unsafe { talck.alloc(layout) };
+                    unsafe { talck.dealloc(freed, layout) };
+
+                    // moving the allocation keeps the thread it's attributed to
+                    let leaked = unsafe { talck.realloc(leaked, layout, 1000) };
+                    (leaked as usize, std::thread::current().id())
+                })
+                .join()
+                .unwrap()
+        });
+
+        let main_ptr = unsafe { talck.alloc(layout) };
+
+        let mut live = std::vec::Vec::new();
+        talck.for_each_live_allocation(|ptr, thread| live.push((ptr.as_ptr() as usize, thread)));
+        live.sort_by_key(|&(addr, _)| addr);
+
+        let mut expected = [(leaked, leaker), (main_ptr as usize, std::thread::current().id())];
+        expected.sort_by_key(|&(addr, _)| addr);
+        assert_eq!(live, expected);
+
+        unsafe { talck.dealloc(main_ptr, layout) };
+        unsafe { talck.dealloc(leaked as *mut u8, Layout::from_size_align(1000, 8).unwrap()) };
+
+        talck.for_each_live_allocation(|_, _| panic!("all allocations were freed"));
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn metrics_test() {
+        use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, SharedString, Unit};
+        use std::collections::HashMap;
+        use std::sync::{
+            atomic::{AtomicU64, Ordering as AtomicOrdering},
+            Arc, Mutex,
+        };
+
+        #[derive(Default)]
+        struct TestRecorder(Mutex<HashMap<String, Arc<AtomicU64>>>);
+
+        impl TestRecorder {
+            fn handle(&self, key: &Key) -> Arc<AtomicU64> {
+                self.0.lock().unwrap().entry(key.name().to_string()).or_default().clone()
+            }
+
+            fn get(&self, name: &str) -> u64 {
+                self.0.lock().unwrap()[name].load(AtomicOrdering::Relaxed)
+            }
+        }
+
+        impl metrics::Recorder for TestRecorder {
+            fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+            fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+            fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+            fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+                Counter::from_arc(self.handle(key))
+            }
+            fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+                Gauge::from_arc(self.handle(key))
+            }
+            fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+                Histogram::noop()
+            }
+        }
+
+        let names = MetricNames { allocated_bytes: "test_allocated_bytes", ..MetricNames::DEFAULT };
+        let talck = Talc::new(ErrOnOom).lock::<spin::Mutex<()>>().with_metric_names(names);
+        let mut arena = [0u8; 10000];
+        unsafe { talck.lock().claim(Span::from(&mut arena)).unwrap() };
+
+        let recorder = TestRecorder::default();
+        let gauge = |name| f64::from_bits(recorder.get(name));
+        let layout = Layout::new::<[u64; 8]>();
+
+        let ptr = metrics::with_local_recorder(&recorder, || unsafe { talck.alloc(layout) });
+        assert_eq!(gauge("test_allocated_bytes"), layout.size() as f64);
+        assert_eq!(gauge("talc_allocation_count"), 1.0);
+        assert_eq!(recorder.get("talc_total_allocation_count"), 1);
+        assert!(gauge("talc_claimed_bytes") > 0.0);
+
+        metrics::with_local_recorder(&recorder, || unsafe { talck.dealloc(ptr, layout) });
+        assert_eq!(gauge("test_allocated_bytes"), 0.0);
+        assert_eq!(gauge("talc_allocation_count"), 0.0);
+        assert_eq!(recorder.get("talc_total_allocation_count"), 1);
+    }
+}
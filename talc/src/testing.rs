@@ -0,0 +1,87 @@
+//! Utilities to make testing code that allocates with Talc more ergonomic.
+//!
+//! These are intended for tests only, as they panic instead of handling allocation failure.
+
+#[cfg(feature = "allocator")]
+use core::alloc::{AllocError, Allocator};
+
+#[cfg(all(feature = "allocator-api2", not(feature = "allocator")))]
+use allocator_api2::alloc::{AllocError, Allocator};
+
+use core::{alloc::Layout, ptr::NonNull};
+
+/// An [`Allocator`] adapter that panics instead of returning [`AllocError`].
+///
+/// Failures will panic with the offending [`Layout`], making them louder
+/// and removing the need to unwrap or `try_reserve` throughout test code.
+///
+/// This is intended for tests only.
+///
+/// # Example
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # use talc::{*, testing::InfallibleAlloc};
+/// let mut arena = [0u8; 10000];
+/// let talck = Talc::new(ErrOnOom).lock::<spin::Mutex<()>>();
+/// unsafe { talck.lock().claim(arena.as_mut().into()).unwrap(); }
+///
+/// let mut vec = Vec::with_capacity_in(100, InfallibleAlloc(&talck));
+/// vec.extend(0..100usize);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InfallibleAlloc<A: Allocator>(pub A);
+
+#[cold]
+#[track_caller]
+fn alloc_failed(layout: Layout) -> ! {
+    panic!("allocation failed: {:?}", layout)
+}
+
+unsafe impl<A: Allocator> Allocator for InfallibleAlloc<A> {
+    #[track_caller]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        Ok(self.0.allocate(layout).unwrap_or_else(|_| alloc_failed(layout)))
+    }
+
+    #[track_caller]
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        Ok(self.0.allocate_zeroed(layout).unwrap_or_else(|_| alloc_failed(layout)))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.0.deallocate(ptr, layout)
+    }
+
+    #[track_caller]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        Ok(self.0.grow(ptr, old_layout, new_layout).unwrap_or_else(|_| alloc_failed(new_layout)))
+    }
+
+    #[track_caller]
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        Ok(self
+            .0
+            .grow_zeroed(ptr, old_layout, new_layout)
+            .unwrap_or_else(|_| alloc_failed(new_layout)))
+    }
+
+    #[track_caller]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        Ok(self.0.shrink(ptr, old_layout, new_layout).unwrap_or_else(|_| alloc_failed(new_layout)))
+    }
+}